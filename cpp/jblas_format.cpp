@@ -26,6 +26,8 @@ extern "C" {
 
   // generic exception class that we expect to be thrown
   const char *EXCEPTION_CLASS = "com/github/sadikovi/rustjblas/OperationException";
+  // exception class for iterative routines that did not converge, subclass of EXCEPTION_CLASS
+  const char *NOT_CONVERGED_EXCEPTION_CLASS = "com/github/sadikovi/rustjblas/NotConvergedException";
 
   // Get matrix pointer for the current instance
   void* get_matrix_pointer(JNIEnv *env, jobject obj) {
@@ -35,10 +37,22 @@ extern "C" {
     return (void*) ptr;
   }
 
-  // Throw exception with provided message
-  void throw_exception(JNIEnv *env, const char *message) {
-    jclass clazz = env->FindClass(EXCEPTION_CLASS);
-    env->ThrowNew(clazz, message);
+  // Throw exception with provided message, error kind and info code, see ERR_* values.
+  // Message is returned by library and is released after copying.
+  void throw_exception(JNIEnv *env, int kind, int info, const char *message) {
+    jstring jmessage = env->NewStringUTF(message);
+    string_dealloc(message);
+    jobject exception;
+    if (kind == ERR_NOT_CONVERGED) {
+      jclass clazz = env->FindClass(NOT_CONVERGED_EXCEPTION_CLASS);
+      jmethodID init = env->GetMethodID(clazz, "<init>", "(Ljava/lang/String;I)V");
+      exception = env->NewObject(clazz, init, jmessage, info);
+    } else {
+      jclass clazz = env->FindClass(EXCEPTION_CLASS);
+      jmethodID init = env->GetMethodID(clazz, "<init>", "(Ljava/lang/String;II)V");
+      exception = env->NewObject(clazz, init, jmessage, kind, info);
+    }
+    env->Throw((jthrowable) exception);
   }
 
   /* == Bindings == */
@@ -51,11 +65,12 @@ extern "C" {
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_alloc_1from_1array(
       JNIEnv *env, jclass clazz, jint rows, jint cols, jdoubleArray data) {
     jsize len = env->GetArrayLength(data);
-    // always copy elements from java heap
     jdouble *body = env->GetDoubleArrayElements(data, 0);
+    // elements are copied by library, release array without copying back changes
     PtrResult res = alloc_from_array(rows, cols, len, body);
+    env->ReleaseDoubleArrayElements(data, body, JNI_ABORT);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -69,7 +84,7 @@ extern "C" {
       JNIEnv *env, jclass clazz, jint rows, jint cols) {
     PtrResult res = alloc_rand(rows, cols);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -83,7 +98,7 @@ extern "C" {
       JNIEnv *env, jclass clazz, jint rows, jint cols) {
     PtrResult res = alloc_zeros(rows, cols);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -97,7 +112,7 @@ extern "C" {
       JNIEnv *env, jclass clazz, jint rows, jint cols) {
     PtrResult res = alloc_ones(rows, cols);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -111,7 +126,7 @@ extern "C" {
       JNIEnv *env, jclass clazz, jint rows, jint cols) {
     PtrResult res = alloc_identity(rows, cols);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -123,7 +138,11 @@ extern "C" {
    */
   JNIEXPORT jint JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1rows(
       JNIEnv *env, jobject obj) {
-    return matrix_rows(get_matrix_pointer(env, obj));
+    IntResult res = matrix_rows(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -133,7 +152,11 @@ extern "C" {
    */
   JNIEXPORT jint JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1cols(
       JNIEnv *env, jobject obj) {
-    return matrix_cols(get_matrix_pointer(env, obj));
+    IntResult res = matrix_cols(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -145,6 +168,10 @@ extern "C" {
       JNIEnv *env, jobject obj) {
     void *ptr = get_matrix_pointer(env, obj);
     DoubleArray arr = matrix_data_array(ptr);
+    if (arr.err) {
+      throw_exception(env, arr.err_kind, arr.err_info, arr.err);
+      return NULL;
+    }
     jdoubleArray result = env->NewDoubleArray(arr.len);
     env->SetDoubleArrayRegion(result, 0, arr.len, arr.data);
     return result;
@@ -157,8 +184,13 @@ extern "C" {
    */
   JNIEXPORT jstring JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1pretty_1string(
       JNIEnv *env, jobject obj, jint truncate) {
-    const char *cstr = matrix_pretty_string(get_matrix_pointer(env, obj), truncate);
-    jstring result = env->NewStringUTF(cstr);
+    StringResult res = matrix_pretty_string(get_matrix_pointer(env, obj), truncate);
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+      return NULL;
+    }
+    jstring result = env->NewStringUTF(res.ptr);
+    string_dealloc(res.ptr);
     return result;
   }
 
@@ -169,7 +201,10 @@ extern "C" {
    */
   JNIEXPORT void JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1dealloc(
       JNIEnv *env, jobject obj) {
-    matrix_dealloc(get_matrix_pointer(env, obj));
+    VoidResult res = matrix_dealloc(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
   }
 
   /*
@@ -181,7 +216,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     PtrResult res = matrix_add_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -195,7 +230,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     PtrResult res = matrix_add_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -209,7 +244,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     VoidResult res = matrix_add_in_place_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -222,7 +257,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     VoidResult res = matrix_add_in_place_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -235,7 +270,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     PtrResult res = matrix_sub_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -249,7 +284,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     PtrResult res = matrix_sub_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -263,7 +298,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     VoidResult res = matrix_sub_in_place_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -276,7 +311,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     VoidResult res = matrix_sub_in_place_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -289,7 +324,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     PtrResult res = matrix_mul_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -303,7 +338,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     PtrResult res = matrix_mul_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -317,7 +352,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     VoidResult res = matrix_mul_in_place_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -330,7 +365,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     VoidResult res = matrix_mul_in_place_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -343,7 +378,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     PtrResult res = matrix_div_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -357,7 +392,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     PtrResult res = matrix_div_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -371,7 +406,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jdouble scalar) {
     VoidResult res = matrix_div_in_place_scalar(get_matrix_pointer(env, obj), scalar);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -384,7 +419,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     VoidResult res = matrix_div_in_place_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -398,7 +433,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     PtrResult res = matrix_mmul_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -412,7 +447,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jlong aptr) {
     VoidResult res = matrix_mmul_in_place_matrix(get_matrix_pointer(env, obj), (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -423,7 +458,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1column_1mins(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_column_mins(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_column_mins(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -433,7 +472,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1column_1maxs(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_column_maxs(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_column_maxs(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -443,7 +486,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1column_1means(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_column_means(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_column_means(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -453,7 +500,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1column_1sums(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_column_sums(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_column_sums(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -465,7 +516,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jint col, jlong aptr) {
     VoidResult res = matrix_put_column(get_matrix_pointer(env, obj), col, (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -478,7 +529,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jint col) {
     PtrResult res = matrix_get_column(get_matrix_pointer(env, obj), col);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -492,7 +543,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jint row, jlong aptr) {
     VoidResult res = matrix_put_row(get_matrix_pointer(env, obj), row, (void*) aptr);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
   }
 
@@ -505,7 +556,7 @@ extern "C" {
       JNIEnv *env, jobject obj, jint row) {
     PtrResult res = matrix_get_row(get_matrix_pointer(env, obj), row);
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -517,7 +568,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1row_1mins(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_row_mins(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_row_mins(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -527,7 +582,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1row_1maxs(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_row_maxs(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_row_maxs(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -537,7 +596,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1row_1means(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_row_means(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_row_means(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -547,7 +610,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1row_1sums(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_row_sums(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_row_sums(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -557,7 +624,11 @@ extern "C" {
    */
   JNIEXPORT jdouble JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1min(
       JNIEnv *env, jobject obj) {
-    return matrix_min(get_matrix_pointer(env, obj));
+    ScalarResult res = matrix_min(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -567,7 +638,11 @@ extern "C" {
    */
   JNIEXPORT jdouble JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1max(
       JNIEnv *env, jobject obj) {
-    return matrix_max(get_matrix_pointer(env, obj));
+    ScalarResult res = matrix_max(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -577,7 +652,11 @@ extern "C" {
    */
   JNIEXPORT jdouble JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1sum(
       JNIEnv *env, jobject obj) {
-    return matrix_sum(get_matrix_pointer(env, obj));
+    ScalarResult res = matrix_sum(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -587,7 +666,11 @@ extern "C" {
    */
   JNIEXPORT jdouble JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1norm1(
       JNIEnv *env, jobject obj) {
-    return matrix_norm1(get_matrix_pointer(env, obj));
+    ScalarResult res = matrix_norm1(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -597,7 +680,11 @@ extern "C" {
    */
   JNIEXPORT jdouble JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1norm2(
       JNIEnv *env, jobject obj) {
-    return matrix_norm2(get_matrix_pointer(env, obj));
+    ScalarResult res = matrix_norm2(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return res.value;
   }
 
   /*
@@ -607,7 +694,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1transpose(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_transpose(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_transpose(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
@@ -619,7 +710,7 @@ extern "C" {
       JNIEnv *env, jobject obj) {
     PtrResult res = matrix_diag(get_matrix_pointer(env, obj));
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     return (long) res.ptr;
   }
@@ -631,7 +722,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1abs(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_abs(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_abs(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /* == Bindings for singular value decomposition == */
@@ -639,7 +734,7 @@ extern "C" {
   // Set SVD result into pointers array
   void set_svd_result(JNIEnv *env, SvdResult res, jobject ptrs) {
     if (res.err) {
-      throw_exception(env, res.err);
+      throw_exception(env, res.err_kind, res.err_info, res.err);
     }
     // set fields if they are available
     jclass clazz = env->GetObjectClass(ptrs);
@@ -675,7 +770,11 @@ extern "C" {
    */
   JNIEXPORT jlong JNICALL Java_com_github_sadikovi_rustjblas_DoubleMatrix_matrix_1singular_1values(
       JNIEnv *env, jobject obj) {
-    return (long) matrix_singular_values(get_matrix_pointer(env, obj));
+    PtrResult res = matrix_singular_values(get_matrix_pointer(env, obj));
+    if (res.err) {
+      throw_exception(env, res.err_kind, res.err_info, res.err);
+    }
+    return (long) res.ptr;
   }
 
   /*
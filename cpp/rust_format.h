@@ -24,16 +24,34 @@
 extern "C" {
 #endif
 
+/* Error kinds reported in `err_kind` field of result structs */
+#define ERR_NONE 0 /* operation succeeded */
+#define ERR_PANIC 1 /* unexpected failure in library code */
+#define ERR_SHAPE_MISMATCH 2
+#define ERR_INDEX_OUT_OF_BOUNDS 3
+#define ERR_NOT_CONVERGED 4 /* iterative routine did not converge, can be retried */
+#define ERR_SINGULAR 5
+#define ERR_INVALID_ARGUMENT 6
+#define ERR_ALLOCATION_FAILURE 7
+
 /* Pointer result for matrix, contains following fields:
  * `ptr` - raw pointer to the matrix
+ * `err_kind` - error kind, one of ERR_* values, ERR_NONE if operation succeeded
+ * `err_info` - info code of LAPACK or PROPACK routine for ERR_NOT_CONVERGED and ERR_SINGULAR,
+ *              0 otherwise
  * `err` - error message pointer.
  * If `err` pointer is NULL then result is evaluated to Ok, otherwise error occurred and exception
- * should thrown with error message.
+ * should thrown with error message. Error message must be released with `string_dealloc`.
+ *
+ * All other result structs contain `err_kind`, `err_info` and `err` fields with the same meaning.
  *
- * Methods that take external parameters besides pointer to current matrix should return PtrResult.
+ * All methods return result structs, so that errors, including NULL matrix pointers, are reported
+ * instead of unwinding across library boundary.
  */
 typedef struct PtrResult {
   void *ptr;
+  int err_kind;
+  int err_info;
   const char *err;
 } PtrResult;
 
@@ -42,43 +60,171 @@ typedef struct PtrResult {
  * and operation is successful, otherwise, exception should thrown with message `err`.
  */
 typedef struct VoidResult {
+  int err_kind;
+  int err_info;
   const char *err;
 } VoidResult;
 
 /* DoubleArray struct to return array of double values with its length.
- * Added mainly for convenience.
+ * Added mainly for convenience. Array is owned by the matrix and should be copied.
  */
 typedef struct DoubleArray {
   int len;
   const double* data;
+  int err_kind;
+  int err_info;
+  const char *err;
 } DoubleArray;
 
+/* ScalarResult struct to return double value, e.g. sum or norm of the matrix */
+typedef struct ScalarResult {
+  double value;
+  int err_kind;
+  int err_info;
+  const char *err;
+} ScalarResult;
+
+/* IntResult struct to return int value, e.g. number of rows or boolean flag (1 or 0) */
+typedef struct IntResult {
+  int value;
+  int err_kind;
+  int err_info;
+  const char *err;
+} IntResult;
+
+/* StringResult struct to return C string that must be released with `string_dealloc` */
+typedef struct StringResult {
+  const char *ptr;
+  int err_kind;
+  int err_info;
+  const char *err;
+} StringResult;
+
 /* SvdResult struct to return result of singular value decomposition as pointers to u, s and v:
  * `u` - pointer to U matrix
  * `s` - pointer to s matrix (as vector)
  * `v` - pointer to V matrix
- * `err` - err message pointer
+ * `err_kind`, `err_info`, `err` - error fields, see PtrResult
  */
 typedef struct SvdResult {
   void *u;
   void *s;
   void *v;
+  int err_kind;
+  int err_info;
   const char *err;
 } SvdResult;
 
+/* EigenResult struct to return eigenvalues and eigenvectors:
+ * `values` - pointer to eigenvalues (as vector)
+ * `vectors` - pointer to matrix of eigenvectors as columns
+ * `err_kind`, `err_info`, `err` - error fields, see PtrResult
+ */
+typedef struct EigenResult {
+  void *values;
+  void *vectors;
+  int err_kind;
+  int err_info;
+  const char *err;
+} EigenResult;
+
+/* GeneralEigenResult struct to return eigendecomposition of general matrix, complex values are
+ * returned as pairs of real and imaginary parts:
+ * `values_re`, `values_im` - pointers to eigenvalues (as vectors)
+ * `left_re`, `left_im` - pointers to left eigenvectors, NULL if they were not computed
+ * `right_re`, `right_im` - pointers to right eigenvectors, NULL if they were not computed
+ * `err_kind`, `err_info`, `err` - error fields, see PtrResult
+ */
+typedef struct GeneralEigenResult {
+  void *values_re;
+  void *values_im;
+  void *left_re;
+  void *left_im;
+  void *right_re;
+  void *right_im;
+  int err_kind;
+  int err_info;
+  const char *err;
+} GeneralEigenResult;
+
+/* LanczosResult struct to return result of Lanczos SVD with convergence diagnostics:
+ * `u` - pointer to U matrix, NULL if singular vectors were not computed
+ * `s` - pointer to s matrix (as vector) of converged singular values
+ * `v` - pointer to V matrix, NULL if singular vectors were not computed
+ * `bnd` - pointer to error bounds on singular values (as vector)
+ * `info` - 0 if converged, -1 if fewer than k singular triplets converged within max restarts
+ *          (singular vectors are not computed), J > 0 if an invariant subspace of dimension J
+ *          was found
+ * `restarts` - number of restarts
+ * `matvecs` - number of matrix-vector products
+ * `err_kind`, `err_info`, `err` - error fields, see PtrResult
+ */
+typedef struct LanczosResult {
+  void *u;
+  void *s;
+  void *v;
+  void *bnd;
+  int info;
+  int restarts;
+  int matvecs;
+  int err_kind;
+  int err_info;
+  const char *err;
+} LanczosResult;
+
+/* QrResult struct to return QR factorization A * P = Q * R:
+ * `q` - pointer to Q matrix with orthonormal columns
+ * `r` - pointer to upper triangular R matrix
+ * `p` - pointer to permutation matrix P, NULL if column pivoting was not used
+ * `err_kind`, `err_info`, `err` - error fields, see PtrResult
+ */
+typedef struct QrResult {
+  void *q;
+  void *r;
+  void *p;
+  int err_kind;
+  int err_info;
+  const char *err;
+} QrResult;
+
+/* LstsqResult struct to return least squares solution:
+ * `x` - pointer to solution matrix
+ * `residuals` - pointer to 2-norms of residuals for each right-hand side (as vector)
+ * `s` - pointer to singular values (as vector), NULL if they were not computed
+ * `rank` - effective rank of the matrix
+ * `err_kind`, `err_info`, `err` - error fields, see PtrResult
+ */
+typedef struct LstsqResult {
+  void *x;
+  void *residuals;
+  void *s;
+  int rank;
+  int err_kind;
+  int err_info;
+  const char *err;
+} LstsqResult;
+
 /* static methods */
+/* values are copied, caller keeps ownership of the array */
 PtrResult alloc_from_array(int, int, size_t, const double*);
+/* buffer allocated by library that can be adopted by matrix without copying */
+double* alloc_buffer(size_t);
+void buffer_dealloc(double*, size_t);
+/* takes ownership of buffer returned by `alloc_buffer` with the same length */
+PtrResult alloc_from_buffer(int, int, size_t, double*);
 PtrResult alloc_rand(int, int);
 PtrResult alloc_zeros(int, int);
 PtrResult alloc_ones(int, int);
 PtrResult alloc_identity(int, int);
 
 /* instance methods */
-int matrix_rows(void*);
-int matrix_cols(void*);
+IntResult matrix_rows(void*);
+IntResult matrix_cols(void*);
 DoubleArray matrix_data_array(void*);
-const char* matrix_pretty_string(void*, bool);
-void matrix_dealloc(void*);
+StringResult matrix_pretty_string(void*, bool);
+/* Release string returned by library, i.e. `err` field of result or `matrix_pretty_string` */
+void string_dealloc(const char*);
+VoidResult matrix_dealloc(void*);
 
 PtrResult matrix_add_scalar(void*, double);
 PtrResult matrix_add_matrix(void*, void*);
@@ -102,36 +248,76 @@ VoidResult matrix_div_in_place_matrix(void*, void*);
 
 PtrResult matrix_mmul_matrix(void*, void*);
 VoidResult matrix_mmul_in_place_matrix(void*, void*);
+PtrResult matrix_mmul_transpose(void*, void*, int, int);
+VoidResult matrix_gemm(int, int, double, void*, void*, double, void*);
+PtrResult matrix_gram(void*);
+PtrResult matrix_gram_rows(void*);
+VoidResult matrix_syrk(int, double, void*, double, void*);
+PtrResult matrix_mv(void*, void*);
+PtrResult matrix_tmv(void*, void*);
+PtrResult matrix_outer(void*, void*);
+ScalarResult matrix_dot(void*, void*);
+VoidResult matrix_axpy(void*, double, void*);
 
-void* matrix_column_mins(void*);
-void* matrix_column_maxs(void*);
-void* matrix_column_means(void*);
-void* matrix_column_sums(void*);
+PtrResult matrix_column_mins(void*);
+PtrResult matrix_column_maxs(void*);
+PtrResult matrix_column_means(void*);
+PtrResult matrix_column_sums(void*);
 
-void* matrix_row_mins(void*);
-void* matrix_row_maxs(void*);
-void* matrix_row_means(void*);
-void* matrix_row_sums(void*);
+PtrResult matrix_row_mins(void*);
+PtrResult matrix_row_maxs(void*);
+PtrResult matrix_row_means(void*);
+PtrResult matrix_row_sums(void*);
 
 VoidResult matrix_put_column(void*, int, void*);
 PtrResult matrix_get_column(void*, int);
 VoidResult matrix_put_row(void*, int, void*);
 PtrResult matrix_get_row(void*, int);
 
-double matrix_min(void*);
-double matrix_max(void*);
-double matrix_sum(void*);
-double matrix_norm1(void*);
-double matrix_norm2(void*);
+ScalarResult matrix_min(void*);
+ScalarResult matrix_max(void*);
+ScalarResult matrix_sum(void*);
+ScalarResult matrix_norm1(void*);
+ScalarResult matrix_norm2(void*);
 
-void* matrix_transpose(void*);
+PtrResult matrix_transpose(void*);
 PtrResult matrix_diag(void*);
-void* matrix_abs(void*);
+PtrResult matrix_abs(void*);
 
 SvdResult matrix_full_svd(void*);
-void* matrix_singular_values(void*);
+PtrResult matrix_singular_values(void*);
 SvdResult matrix_svd_k(void*, int);
+SvdResult matrix_svd_job_k(void*, int, char);
+SvdResult matrix_svd_index_range(void*, int, int, char);
+SvdResult matrix_svd_value_range(void*, double, double, char);
 SvdResult matrix_lansvd_k(void*, int);
+SvdResult matrix_lansvd_job_k(void*, int, char);
+SvdResult matrix_lansvd_which_k(void*, int, char);
+SvdResult matrix_lansvd_with(void*, int, char, double, int, int, int, int, int);
+LanczosResult matrix_lansvd_result(void*, int, char, double, int, int, int, int, int);
+SvdResult matrix_lansvd_pro_k(void*, int, int);
+SvdResult matrix_rsvd_k(void*, int);
+SvdResult matrix_rsvd_with(void*, int, int, int);
+
+PtrResult matrix_solve(void*, void*);
+PtrResult matrix_inverse(void*);
+ScalarResult matrix_determinant(void*);
+QrResult matrix_qr(void*, int);
+LstsqResult matrix_lstsq(void*, void*, double);
+LstsqResult matrix_lstsq_qr(void*, void*);
+PtrResult matrix_cholesky(void*);
+PtrResult matrix_cholesky_solve(void*, void*);
+IntResult matrix_is_positive_definite(void*);
+EigenResult matrix_eigh(void*);
+EigenResult matrix_eigh_top_k(void*, int);
+EigenResult matrix_eigh_index_range(void*, int, int);
+EigenResult matrix_eigh_value_range(void*, double, double);
+GeneralEigenResult matrix_eig(void*, int, int);
+PtrResult matrix_pinv(void*, double);
+IntResult matrix_rank(void*, double);
+ScalarResult matrix_cond(void*);
+PtrResult matrix_null_space(void*, double);
+PtrResult matrix_range_space(void*, double);
 
 #ifdef __cplusplus
 }
@@ -31,15 +31,47 @@ package com.github.sadikovi.rustjblas;
  * type of exception.
  */
 public class OperationException extends RuntimeException {
+  // Error kinds reported by native library, see `rust_format.h`
+  public static final int KIND_UNKNOWN = 1;
+  public static final int KIND_SHAPE_MISMATCH = 2;
+  public static final int KIND_INDEX_OUT_OF_BOUNDS = 3;
+  public static final int KIND_NOT_CONVERGED = 4;
+  public static final int KIND_SINGULAR = 5;
+  public static final int KIND_INVALID_ARGUMENT = 6;
+  public static final int KIND_ALLOCATION_FAILURE = 7;
+
+  private final int kind;
+  private final int info;
+
+  public OperationException(String message, int kind, int info) {
+    super(message);
+    this.kind = kind;
+    this.info = info;
+  }
+
   public OperationException(String message, Throwable cause) {
     super(message, cause);
+    this.kind = KIND_UNKNOWN;
+    this.info = 0;
   }
 
   public OperationException(String message) {
-    super(message);
+    this(message, KIND_UNKNOWN, 0);
   }
 
   public OperationException() {
     super();
+    this.kind = KIND_UNKNOWN;
+    this.info = 0;
+  }
+
+  /** Error kind, one of `KIND_*` constants */
+  public int getKind() {
+    return kind;
+  }
+
+  /** Info code of LAPACK or PROPACK routine for non-convergence and singularity, 0 otherwise */
+  public int getInfo() {
+    return info;
   }
 }
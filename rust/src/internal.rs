@@ -19,26 +19,56 @@
 // SOFTWARE.
 
 use std::cmp;
-use std::f64::{EPSILON, NAN};
+use std::error;
 use std::fmt::{Display, Error, Formatter};
-use blas::{dasum, daxpy, dcopy, dgemm, dnrm2, dscal};
-use lapack::{dgesdd, dgesvdx};
+use std::mem;
+use std::ptr;
+use blas::{dasum, daxpy, dcopy, ddot, dgemm, dgemv, dger, dnrm2, dscal, dsyrk};
+use lapack::{dgels, dgeqp3, dgeqrf, dgesdd, dgesvdx, dgetrf, dgetri, dgetrs, dorgqr};
+use lapack::{dgeev, dpotrf, dpotrs, dsyevd, dsyevr};
 use rand::{Rng, weak_rng};
-use lanczos::dlansvd_irl;
+use lanczos::{LanczosOptions, LinearOperator, OperatorContext, PROPACK_EXTERN};
+use lanczos::{CountingContext, counting_matmul, dense_matmul, dlansvd, dlansvd_irl};
+use lanczos::{operator_matmul, propack_call, transposed_dense_matmul};
+
+// Macro to return error from the enclosing function when condition does not hold, error is only
+// constructed on failure
+macro_rules! ensure {
+    ($cond:expr, $err:expr) => (
+        if !$cond {
+            return Err($err);
+        }
+    )
+}
 
-// Macro to assert matrices shapes
-macro_rules! assert_shape {
+// Macro to check matrices shapes
+macro_rules! check_shape {
     ($x:expr, $y:expr) => (
-        assert_eq!($x, $y, "Shape mismatch: {:?} != {:?}.", $x, $y);
+        ensure!($x == $y,
+            MatrixError::ShapeMismatch(format!("Shape mismatch: {:?} != {:?}.", $x, $y)));
+    )
+}
+
+// Macro to check info code returned by LAPACK workspace query
+macro_rules! check_workspace {
+    ($info:expr) => (
+        ensure!($info == 0, MatrixError::InvalidArgument(
+            format!("Workspace query failed to execute with code {}.", $info)));
     )
 }
 
 // Macro to generate vectorized elementwise matrix operations (experimental)
 macro_rules! vectorized_op {
-    ($fn_matrix_mut:ident, $fn_matrix:ident, $op:tt) => (
+    ($fn_matrix_mut:ident, $fn_matrix:ident, $try_fn_matrix_mut:ident, $try_fn_matrix:ident,
+        $op:tt) => (
         #[inline]
         pub fn $fn_matrix_mut(&mut self, other: &DoubleMatrix) {
-            assert_shape!(self.shape(), other.shape());
+            unwrap_or_panic(self.$try_fn_matrix_mut(other))
+        }
+
+        #[inline]
+        pub fn $try_fn_matrix_mut(&mut self, other: &DoubleMatrix) -> MatrixResult<()> {
+            check_shape!(self.shape(), other.shape());
             // load default non-vectorized implementation when instructions are not available
             let mut i = 0;
             let len = self.data.len();
@@ -53,13 +83,19 @@ macro_rules! vectorized_op {
                 self.data[i] = self.data[i] $op other.data[i];
                 i += 1;
             }
+            Ok(())
         }
 
         #[inline]
         pub fn $fn_matrix(&self, other: &DoubleMatrix) -> Self {
+            unwrap_or_panic(self.$try_fn_matrix(other))
+        }
+
+        #[inline]
+        pub fn $try_fn_matrix(&self, other: &DoubleMatrix) -> MatrixResult<Self> {
             let mut clone = self.clone();
-            clone.$fn_matrix_mut(other);
-            clone
+            clone.$try_fn_matrix_mut(other)?;
+            Ok(clone)
         }
     )
 }
@@ -80,21 +116,21 @@ macro_rules! dgesdd_op {
                 $jobz as u8, // jobz: u8,
                 $rows as i32, // m: i32,
                 $cols as i32, // n: i32,
-                &mut vec![], // a: &mut [f64],
+                &mut [], // a: &mut [f64],
                 cmp::max(1, $rows) as i32, // lda: i32,
-                &mut vec![], // s: &mut [f64],
-                &mut vec![], // u: &mut [f64],
+                &mut [], // s: &mut [f64],
+                &mut [], // u: &mut [f64],
                 cmp::max(1, $urows) as i32, // ldu: i32,
-                &mut vec![], // vt: &mut [f64],
+                &mut [], // vt: &mut [f64],
                 cmp::max(1, $vtrows) as i32, // ldvt: i32,
                 &mut work, // work: &mut [f64],
                 lwork, // lwork: i32,
-                &mut vec![], // iwork: &mut [i32],
+                &mut [], // iwork: &mut [i32],
                 &mut info // info: &mut i32
             );
         }
 
-        assert!(info == 0, "Workspace query failed to execute with code {}.", info);
+        check_workspace!(info);
 
         // additional workspace data structures after adjustment
         let lwork = work[0] as usize;
@@ -120,7 +156,8 @@ macro_rules! dgesdd_op {
         }
 
         // this is strict check; when info is negative, then ith parameter has illegal value
-        assert!(info == 0, "GESDD did not converge, {}.", info);
+        ensure!(info == 0,
+            MatrixError::NotConverged(format!("GESDD did not converge, {}.", info), info));
     }}
 }
 
@@ -142,6 +179,66 @@ macro_rules! dcopy {
     }}
 }
 
+// Error of matrix operation, each variant contains error message; LAPACK-based operations also
+// report info code returned by the routine.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatrixError {
+    ShapeMismatch(String), // shapes or dimensions of operands are incompatible
+    IndexOutOfBounds(String), // row or column index is outside of the matrix
+    NotConverged(String, i32), // iterative algorithm did not converge, contains info code
+    Singular(String, i32), // matrix is singular or not positive definite, contains info code
+    InvalidArgument(String), // argument is invalid, e.g. number of singular values or range
+    AllocationFailure(String) // matrix is too large to allocate
+}
+
+impl MatrixError {
+    // Return error message
+    pub fn message(&self) -> &str {
+        match *self {
+            MatrixError::ShapeMismatch(ref msg) => msg,
+            MatrixError::IndexOutOfBounds(ref msg) => msg,
+            MatrixError::NotConverged(ref msg, _) => msg,
+            MatrixError::Singular(ref msg, _) => msg,
+            MatrixError::InvalidArgument(ref msg) => msg,
+            MatrixError::AllocationFailure(ref msg) => msg
+        }
+    }
+
+    // Return info code of LAPACK or PROPACK routine, if available
+    pub fn info(&self) -> Option<i32> {
+        match *self {
+            MatrixError::NotConverged(_, info) => Some(info),
+            MatrixError::Singular(_, info) => Some(info),
+            _ => None
+        }
+    }
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl error::Error for MatrixError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
+
+// Result of fallible matrix operation
+pub type MatrixResult<T> = Result<T, MatrixError>;
+
+// Unwrap result of fallible operation, panics with error message otherwise; used to implement
+// panicking counterparts of `try_*` methods
+#[inline]
+pub(crate) fn unwrap_or_panic<T>(res: MatrixResult<T>) -> T {
+    match res {
+        Ok(value) => value,
+        Err(err) => panic!("{}", err)
+    }
+}
+
 // Strict representation of the double matrix with as little overhead as possible.
 // This allows us to resolve library conflicts and implement transformations efficiently.
 #[derive(Debug)]
@@ -159,13 +256,461 @@ pub struct SVD {
     pub v: Option<DoubleMatrix> // right singular vectors
 }
 
+impl SVD {
+    // Convert SVD of transposed matrix into SVD of original matrix by swapping left and right
+    // singular vectors, since A^T = V * S * U^T
+    pub fn transpose(self) -> SVD {
+        SVD { u: self.v, s: self.s, v: self.u }
+    }
+
+    // Number of singular values in this decomposition, see `DoubleMatrix::rank` for numerical rank
+    #[inline]
+    pub fn num_values(&self) -> usize {
+        self.s.rows()
+    }
+
+    // Reconstruct matrix U * diag(s) * V^T from singular triplets, returns best rank-r
+    // approximation for truncated SVD. Requires both left and right singular vectors.
+    pub fn reconstruct(&self) -> DoubleMatrix {
+        unwrap_or_panic(self.try_reconstruct())
+    }
+
+    // Reconstruct matrix from singular triplets, returns error if singular vectors are missing
+    pub fn try_reconstruct(&self) -> MatrixResult<DoubleMatrix> {
+        let (u, v) = self.vectors()?;
+        let r = self.num_values();
+        // scale first r columns of U by singular values
+        let mut us = DoubleMatrix::zeros(u.rows(), r);
+        let rows = u.rows();
+        for j in 0..r {
+            let sigma = self.s.get(j, 0);
+            for i in 0..rows {
+                us.data[j * rows + i] = u.data[j * rows + i] * sigma;
+            }
+        }
+        let vt = DoubleMatrix::new(v.rows(), r, v.data[0..v.rows() * r].to_vec()).transpose();
+        Ok(us.mmul(&vt))
+    }
+
+    // Truncate decomposition to the first r singular triplets, singular vectors of full SVD are
+    // truncated to r columns as well
+    pub fn truncate(self, r: usize) -> SVD {
+        unwrap_or_panic(self.try_truncate(r))
+    }
+
+    // Truncate decomposition to the first r singular triplets, returns error for invalid rank
+    pub fn try_truncate(self, r: usize) -> MatrixResult<SVD> {
+        ensure!(r <= self.num_values(), MatrixError::InvalidArgument(
+            format!("Invalid rank for truncation: {} ({} values).", r, self.num_values())));
+        let mut s = self.s.data;
+        s.truncate(r);
+        Ok(SVD {
+            u: self.u.map(|u| u.truncate_columns(r)),
+            s: DoubleMatrix::new(r, 1, s),
+            v: self.v.map(|v| v.truncate_columns(r))
+        })
+    }
+
+    // Frobenius norm of the difference between matrix and its reconstruction
+    pub fn reconstruction_error(&self, a: &DoubleMatrix) -> f64 {
+        a.sub_matrix(&self.reconstruct()).norm2()
+    }
+
+    // Compute fraction of total variance explained by each singular value, s(i)^2 / ||A||_F^2, as
+    // column vector; for column-centered data this is explained variance ratio of principal
+    // components. Matrix is used to compute total variance, since truncated SVD does not have all
+    // singular values.
+    pub fn explained_variance_ratio(&self, a: &DoubleMatrix) -> DoubleMatrix {
+        let total = a.norm2().powi(2);
+        let ratios = self.s.data().iter()
+            .map(|value| if total > 0f64 { value * value / total } else { 0f64 })
+            .collect::<Vec<f64>>();
+        DoubleMatrix::new(self.num_values(), 1, ratios)
+    }
+
+    // Canonicalize signs of singular vectors, so that largest in absolute value component of each
+    // column of U is positive, corresponding column of V is flipped as well; when U is not
+    // computed, V is used instead. Makes results of different SVD methods comparable.
+    pub fn canonicalize_signs(&mut self) {
+        for j in 0..self.num_values() {
+            let flip = {
+                let w = match (self.u.as_ref(), self.v.as_ref()) {
+                    (Some(u), _) => u,
+                    (None, Some(v)) => v,
+                    (None, None) => return
+                };
+                let rows = w.rows();
+                let col = &w.data[j * rows..(j + 1) * rows];
+                let max = col.iter()
+                    .fold(0f64, |acc, &x| if x.abs() > acc.abs() { x } else { acc });
+                max < 0f64
+            };
+            if flip {
+                for w in self.u.iter_mut().chain(self.v.iter_mut()) {
+                    let rows = w.rows();
+                    for value in &mut w.data[j * rows..(j + 1) * rows] {
+                        *value = -*value;
+                    }
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn vectors(&self) -> MatrixResult<(&DoubleMatrix, &DoubleMatrix)> {
+        match (self.u.as_ref(), self.v.as_ref()) {
+            (Some(u), Some(v)) => Ok((u, v)),
+            _ => Err(MatrixError::InvalidArgument(
+                "Both left and right singular vectors are required.".to_string()))
+        }
+    }
+}
+
+// Selects singular vectors to compute in truncated SVD
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvdJob {
+    Values, // singular values only
+    Left, // singular values and left singular vectors
+    Right, // singular values and right singular vectors
+    Both // singular values, left and right singular vectors
+}
+
+impl SvdJob {
+    // Parse job from character: 'N' (values only), 'U' (left), 'V' (right), 'A' (both)
+    pub fn from_char(value: char) -> Self {
+        unwrap_or_panic(Self::try_from_char(value))
+    }
+
+    // Parse job from character, returns error for unsupported character
+    pub fn try_from_char(value: char) -> MatrixResult<Self> {
+        match value {
+            'N' | 'n' => Ok(SvdJob::Values),
+            'U' | 'u' => Ok(SvdJob::Left),
+            'V' | 'v' => Ok(SvdJob::Right),
+            'A' | 'a' => Ok(SvdJob::Both),
+            other => Err(MatrixError::InvalidArgument(
+                format!("Invalid SVD job '{}', expected 'N', 'U', 'V' or 'A'.", other)))
+        }
+    }
+
+    // Whether or not left singular vectors should be computed
+    #[inline]
+    pub fn left(&self) -> bool {
+        *self == SvdJob::Left || *self == SvdJob::Both
+    }
+
+    // Whether or not right singular vectors should be computed
+    #[inline]
+    pub fn right(&self) -> bool {
+        *self == SvdJob::Right || *self == SvdJob::Both
+    }
+
+    // Job for transposed matrix, swaps left and right singular vectors
+    pub fn transpose(&self) -> Self {
+        match *self {
+            SvdJob::Left => SvdJob::Right,
+            SvdJob::Right => SvdJob::Left,
+            other => other
+        }
+    }
+}
+
+// Selects subset of singular values to compute in DGESVDX-based SVD
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvdRange {
+    Index(usize, usize), // singular values with 0-based indices in [start, end), largest first
+    Value(f64, f64) // singular values in half-open interval (vl, vu], vu can be infinite
+}
+
+// Convergence status of Lanczos SVD, derived from DLANSVD_IRL info code
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LanczosStatus {
+    Converged, // all requested singular triplets converged, info = 0
+    // fewer than requested singular triplets converged within max restarts, DLANSVD_IRL returns
+    // info = 0 in this case, which is reported as info = -1
+    NotConverged,
+    InvariantSubspace(usize) // invariant subspace of dimension J was found, info = J > 0
+}
+
+impl LanczosStatus {
+    // Return info code for the status, see `LanczosStatus` for values
+    pub fn info(&self) -> i32 {
+        match *self {
+            LanczosStatus::Converged => 0,
+            LanczosStatus::NotConverged => -1,
+            LanczosStatus::InvariantSubspace(dim) => dim as i32
+        }
+    }
+}
+
+// Lanczos singular value decomposition with convergence diagnostics.
+// When status is not `Converged`, `svd` contains only converged singular values, and singular
+// vectors are not computed if requested triplets did not converge.
+#[derive(Debug)]
+pub struct LanczosSvd {
+    pub svd: SVD, // converged singular triplets
+    pub bnd: DoubleMatrix, // error bounds on singular values as column vector
+    pub requested: usize, // number of requested singular triplets
+    pub restarts: usize, // number of restarts used
+    pub matvecs: usize, // number of matrix-vector products
+    pub status: LanczosStatus // convergence status
+}
+
+impl LanczosSvd {
+    // Return number of converged singular triplets
+    #[inline]
+    pub fn converged(&self) -> usize {
+        self.svd.s.rows()
+    }
+
+    // Return SVD if all requested singular triplets converged, otherwise panic with details
+    pub fn into_svd(self) -> SVD {
+        unwrap_or_panic(self.try_into_svd())
+    }
+
+    // Return SVD if all requested singular triplets converged, otherwise return error with
+    // DLANSVD_IRL info code
+    pub fn try_into_svd(self) -> MatrixResult<SVD> {
+        let info = self.status.info();
+        match self.status {
+            LanczosStatus::Converged => Ok(self.svd),
+            LanczosStatus::NotConverged => Err(MatrixError::NotConverged(format!(
+                "DLANSVD_IRL, K singular triplets did not converge within KMAX iterations, \
+                {} of {} converged after {} restarts.",
+                self.converged(), self.requested, self.restarts), info)),
+            LanczosStatus::InvariantSubspace(dim) => Err(MatrixError::NotConverged(format!(
+                "DLANSVD_IRL, an invariant subspace of dimension J was found, {}.", dim), info))
+        }
+    }
+}
+
+// LU factorization with partial pivoting A = P * L * U of M-by-N matrix, where L is lower
+// triangular with unit diagonal and U is upper triangular (upper trapezoidal when M < N).
+#[derive(Debug)]
+pub struct LU {
+    lu: DoubleMatrix, // L and U factors as computed by DGETRF, unit diagonal of L is not stored
+    ipiv: Vec<i32>, // 1-based pivot indices, row i was interchanged with row ipiv[i]
+    info: i32 // when positive, U(info - 1, info - 1) is exactly zero
+}
+
+impl LU {
+    // Return lower triangular factor with unit diagonal, M-by-min(M, N) matrix
+    pub fn l(&self) -> DoubleMatrix {
+        let (rows, cols) = self.lu.shape();
+        let k = cmp::min(rows, cols);
+        let mut l = DoubleMatrix::zeros(rows, k);
+        for j in 0..k {
+            l.put(j, j, 1f64);
+            for i in j + 1..rows {
+                l.put(i, j, self.lu.get(i, j));
+            }
+        }
+        l
+    }
+
+    // Return upper triangular factor, min(M, N)-by-N matrix
+    pub fn u(&self) -> DoubleMatrix {
+        let (rows, cols) = self.lu.shape();
+        let k = cmp::min(rows, cols);
+        let mut u = DoubleMatrix::zeros(k, cols);
+        for j in 0..cols {
+            for i in 0..cmp::min(j + 1, k) {
+                u.put(i, j, self.lu.get(i, j));
+            }
+        }
+        u
+    }
+
+    // Return M-by-M permutation matrix P
+    pub fn p(&self) -> DoubleMatrix {
+        let rows = self.lu.rows();
+        // apply row interchanges in order to find original row for each row of L * U
+        let mut perm = (0..rows).collect::<Vec<usize>>();
+        for i in 0..self.ipiv.len() {
+            perm.swap(i, self.ipiv[i] as usize - 1);
+        }
+        let mut p = DoubleMatrix::zeros(rows, rows);
+        for (i, &row) in perm.iter().enumerate() {
+            p.put(row, i, 1f64);
+        }
+        p
+    }
+
+    // Return 1-based pivot indices as computed by DGETRF
+    pub fn pivots(&self) -> &[i32] {
+        &self.ipiv
+    }
+
+    // Whether or not factor U has exactly zero diagonal element
+    pub fn is_singular(&self) -> bool {
+        self.info > 0
+    }
+
+    // Compute determinant of the original square matrix, returns 0 for singular matrix
+    pub fn determinant(&self) -> f64 {
+        unwrap_or_panic(self.try_determinant())
+    }
+
+    // Compute determinant, returns error if matrix is not square
+    pub fn try_determinant(&self) -> MatrixResult<f64> {
+        self.check_square()?;
+        let mut det = 1f64;
+        for i in 0..self.lu.rows() {
+            det *= self.lu.get(i, i);
+            if self.ipiv[i] != (i + 1) as i32 {
+                det = -det;
+            }
+        }
+        Ok(det)
+    }
+
+    // Solve A * X = B for X using this factorization, each column of B is a right-hand side
+    pub fn solve(&self, b: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_solve(b))
+    }
+
+    // Solve A * X = B, returns error if matrix is singular or dimensions do not match
+    pub fn try_solve(&self, b: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        self.check_nonsingular()?;
+        let n = self.lu.rows();
+        ensure!(b.rows() == n, MatrixError::ShapeMismatch(format!(
+            "Dimensions mismatch for solve: {} (rows of b) != {} (n).", b.rows(), n)));
+        let mut x = dcopy![b.data()];
+        let mut info = 0i32;
+
+        unsafe {
+            dgetrs(
+                b'N', // trans: u8,
+                n as i32, // n: i32,
+                b.cols() as i32, // nrhs: i32,
+                self.lu.data(), // a: &[f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                &self.ipiv, // ipiv: &[i32],
+                &mut x, // b: &mut [f64],
+                cmp::max(1, n) as i32, // ldb: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        ensure!(info == 0, MatrixError::InvalidArgument(
+            format!("GETRS failed to execute with code {}.", info)));
+        Ok(DoubleMatrix::new(n, b.cols(), x))
+    }
+
+    // Compute inverse of the original square matrix using this factorization
+    pub fn inverse(&self) -> DoubleMatrix {
+        unwrap_or_panic(self.try_inverse())
+    }
+
+    // Compute inverse, returns error if matrix is singular
+    pub fn try_inverse(&self) -> MatrixResult<DoubleMatrix> {
+        self.check_nonsingular()?;
+        let n = self.lu.rows();
+        let mut a = dcopy![self.lu.data()];
+        let mut info = 0i32;
+
+        // estimate size of lwork
+        let lwork = -1;
+        let mut work = vec![0f64; 1];
+
+        unsafe {
+            dgetri(n as i32, &mut [], cmp::max(1, n) as i32, &self.ipiv, &mut work, lwork,
+                &mut info);
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let mut work = vec![0f64; lwork];
+
+        unsafe {
+            dgetri(n as i32, &mut a, cmp::max(1, n) as i32, &self.ipiv, &mut work, lwork as i32,
+                &mut info);
+        }
+
+        ensure!(info == 0, MatrixError::InvalidArgument(
+            format!("GETRI failed to execute with code {}.", info)));
+        Ok(DoubleMatrix::new(n, n, a))
+    }
+
+    #[inline]
+    fn check_square(&self) -> MatrixResult<()> {
+        self.lu.check_square()
+    }
+
+    #[inline]
+    fn check_nonsingular(&self) -> MatrixResult<()> {
+        self.check_square()?;
+        ensure!(!self.is_singular(), MatrixError::Singular(format!(
+            "Matrix is singular: U({}, {}) is exactly zero, cannot solve or invert.",
+            self.info - 1, self.info - 1), self.info));
+        Ok(())
+    }
+}
+
+// QR factorization A * P = Q * R of M-by-N matrix, where P is identity unless column pivoting
+// was requested.
+#[derive(Debug)]
+pub struct QR {
+    pub q: DoubleMatrix, // M-by-min(M, N) matrix with orthonormal columns
+    pub r: DoubleMatrix, // min(M, N)-by-N upper triangular (trapezoidal) matrix
+    pub p: Option<DoubleMatrix> // N-by-N permutation matrix, None when pivoting is not used
+}
+
+// Solution of linear least squares problem min || B - A * X || for each column of B
+#[derive(Debug)]
+pub struct LeastSquares {
+    pub x: DoubleMatrix, // N-by-NRHS solution
+    pub rank: usize, // effective rank of A
+    pub residuals: DoubleMatrix, // 2-norms of residuals B - A * X as column vector of length NRHS
+    pub s: Option<DoubleMatrix> // singular values of A, only set when computed by DGELSD
+}
+
+// Eigendecomposition A = V * diag(w) * V^T of symmetric matrix
+#[derive(Debug)]
+pub struct Eigen {
+    pub values: DoubleMatrix, // eigenvalues as column vector
+    pub vectors: DoubleMatrix // orthonormal eigenvectors as columns, in order of eigenvalues
+}
+
+// Selects subset of eigenvalues to compute in DSYEVR-based eigendecomposition
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EigenRange {
+    Index(usize, usize), // eigenvalues with 0-based indices in [start, end), in ascending order
+    Value(f64, f64) // eigenvalues in half-open interval (vl, vu], bounds can be infinite
+}
+
+// Complex matrix represented as pair of real matrices of the same shape
+#[derive(Debug)]
+pub struct ComplexMatrix {
+    pub re: DoubleMatrix, // real part
+    pub im: DoubleMatrix // imaginary part
+}
+
+// Eigendecomposition of general square matrix, A * v(j) = w(j) * v(j) for right eigenvectors and
+// u(j)^H * A = w(j) * u(j)^H for left eigenvectors. Complex conjugate eigenvalues appear
+// consecutively with positive imaginary part first, eigenvectors are normalized to have
+// Euclidean norm 1 and largest component real.
+#[derive(Debug)]
+pub struct GeneralEigen {
+    pub values: ComplexMatrix, // eigenvalues as column vectors
+    pub left: Option<ComplexMatrix>, // left eigenvectors as columns, None when not computed
+    pub right: Option<ComplexMatrix> // right eigenvectors as columns, None when not computed
+}
+
 impl DoubleMatrix {
     // Create new matrix from data in column-major order
     pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Self {
-        assert_eq!(rows * cols, data.len(),
-            "Dimensions mismatch: {} * {} != {}.", rows, cols, data.len());
+        unwrap_or_panic(Self::try_new(rows, cols, data))
+    }
+
+    // Create new matrix from data in column-major order, returns error if length of data does not
+    // match dimensions
+    pub fn try_new(rows: usize, cols: usize, data: Vec<f64>) -> MatrixResult<Self> {
+        ensure!(rows.checked_mul(cols) == Some(data.len()), MatrixError::ShapeMismatch(
+            format!("Dimensions mismatch: {} * {} != {}.", rows, cols, data.len())));
         // use shrink_to_fit
-        DoubleMatrix { rows: rows, cols: cols, data: data }
+        Ok(DoubleMatrix { rows, cols, data })
     }
 
     // Create new matrix from data in row-major order, used only for testing
@@ -184,31 +729,79 @@ impl DoubleMatrix {
 
     // Create new matrix of 1s
     pub fn ones(rows: usize, cols: usize) -> Self {
-        Self::new(rows, cols, vec![1f64; rows * cols])
+        unwrap_or_panic(Self::try_ones(rows, cols))
+    }
+
+    // Create new matrix of 1s, returns error if number of elements overflows or memory cannot be
+    // allocated
+    pub fn try_ones(rows: usize, cols: usize) -> MatrixResult<Self> {
+        let data = Self::try_alloc(rows, cols, 1f64)?;
+        Self::try_new(rows, cols, data)
     }
 
     // Create new matrix of 0s
     pub fn zeros(rows: usize, cols: usize) -> Self {
-        Self::new(rows, cols, vec![0f64; rows * cols])
+        unwrap_or_panic(Self::try_zeros(rows, cols))
+    }
+
+    // Create new matrix of 0s, returns error if number of elements overflows or memory cannot be
+    // allocated
+    pub fn try_zeros(rows: usize, cols: usize) -> MatrixResult<Self> {
+        let data = Self::try_alloc(rows, cols, 0f64)?;
+        Self::try_new(rows, cols, data)
+    }
+
+    // Allocate data of (rows x cols) matrix filled with value, returns error instead of aborting
+    // when memory cannot be allocated
+    pub fn try_alloc(rows: usize, cols: usize, value: f64) -> MatrixResult<Vec<f64>> {
+        let len = Self::checked_len(rows, cols)?;
+        let mut data = Vec::new();
+        data.try_reserve_exact(len).map_err(|_| MatrixError::AllocationFailure(
+            format!("Failed to allocate matrix: {} * {} elements.", rows, cols)))?;
+        data.resize(len, value);
+        Ok(data)
+    }
+
+    // Number of elements in (rows x cols) matrix, returns error if data of such matrix cannot be
+    // allocated, i.e. its size in bytes exceeds isize::MAX
+    pub fn checked_len(rows: usize, cols: usize) -> MatrixResult<usize> {
+        match rows.checked_mul(cols) {
+            Some(len) if len <= isize::MAX as usize / mem::size_of::<f64>() => Ok(len),
+            _ => Err(MatrixError::AllocationFailure(
+                format!("Matrix is too large to allocate: {} * {} elements.", rows, cols)))
+        }
     }
 
     // Generate matrix of random values
     // This method is faster than method in nalgebra crate
     pub fn new_random(rows: usize, cols: usize) -> Self {
+        unwrap_or_panic(Self::try_new_random(rows, cols))
+    }
+
+    // Generate matrix of random values, returns error if matrix cannot be allocated
+    pub fn try_new_random(rows: usize, cols: usize) -> MatrixResult<Self> {
+        let mut data = Self::try_alloc(rows, cols, 0f64)?;
         let mut rng = weak_rng();
-        let data = rng.gen_iter::<f64>().take(rows * cols).collect::<Vec<f64>>();
-        Self::new(rows, cols, data)
+        for value in &mut data {
+            *value = rng.gen::<f64>();
+        }
+        Self::try_new(rows, cols, data)
     }
 
     // Generate identity matrix that has 1s as main diagonal and the rest are 0s.
     // If matrix is not square then the largest upper square diagonal is 1s
     pub fn identity(rows: usize, cols: usize) -> Self {
-        let mut matrix = Self::zeros(rows, cols);
+        unwrap_or_panic(Self::try_identity(rows, cols))
+    }
+
+    // Generate identity matrix, returns error if matrix cannot be allocated
+    pub fn try_identity(rows: usize, cols: usize) -> MatrixResult<Self> {
+        let mut matrix = Self::try_zeros(rows, cols)?;
         let dim = cmp::min(rows, cols);
         for i in 0..dim {
             matrix.put(i, i, 1f64);
         }
-        matrix
+        Ok(matrix)
     }
 
     // Convert row and col indices into vector index, no boundary checking is performed
@@ -265,6 +858,34 @@ impl DoubleMatrix {
         self.data[i]
     }
 
+    // Put value with boundary checking, returns error if index is out of bounds
+    pub fn try_put(&mut self, row: usize, col: usize, value: f64) -> MatrixResult<()> {
+        self.check_index(row, col)?;
+        self.put(row, col, value);
+        Ok(())
+    }
+
+    // Get value with boundary checking, returns error if index is out of bounds
+    pub fn try_get(&self, row: usize, col: usize) -> MatrixResult<f64> {
+        self.check_index(row, col)?;
+        Ok(self.get(row, col))
+    }
+
+    #[inline]
+    fn check_index(&self, row: usize, col: usize) -> MatrixResult<()> {
+        ensure!(row < self.rows && col < self.cols, MatrixError::IndexOutOfBounds(
+            format!("Invalid index ({}, {}) for shape {:?}.", row, col, self.shape())));
+        Ok(())
+    }
+
+    #[inline]
+    fn check_square(&self) -> MatrixResult<()> {
+        let (rows, cols) = self.shape();
+        ensure!(rows == cols, MatrixError::ShapeMismatch(
+            format!("Matrix is not square: ({}, {}).", rows, cols)));
+        Ok(())
+    }
+
     // Elementwise matrix-scalar operations
 
     #[inline]
@@ -317,73 +938,125 @@ impl DoubleMatrix {
 
     #[inline]
     pub fn add_matrix_mut(&mut self, other: &DoubleMatrix) {
-        assert_shape!(self.shape(), other.shape());
-        let y = self.data_mut();
-        let x = other.data();
-        unsafe { daxpy(y.len() as i32, 1f64, x, 1i32, y, 1i32); }
+        self.axpy(1f64, other);
     }
 
     #[inline]
     pub fn add_matrix(&self, other: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_add_matrix(other))
+    }
+
+    #[inline]
+    pub fn try_add_matrix(&self, other: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
         let mut clone = self.clone();
-        clone.add_matrix_mut(other);
-        clone
+        clone.try_axpy(1f64, other)?;
+        Ok(clone)
     }
 
     #[inline]
     pub fn sub_matrix_mut(&mut self, other: &DoubleMatrix) {
-        assert_shape!(self.shape(), other.shape());
-        let y = self.data_mut();
-        let x = other.data();
-        unsafe { daxpy(y.len() as i32, -1f64, x, 1i32, y, 1i32); }
+        self.axpy(-1f64, other);
     }
 
     #[inline]
     pub fn sub_matrix(&self, other: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_sub_matrix(other))
+    }
+
+    #[inline]
+    pub fn try_sub_matrix(&self, other: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
         let mut clone = self.clone();
-        clone.sub_matrix_mut(other);
-        clone
+        clone.try_axpy(-1f64, other)?;
+        Ok(clone)
     }
 
     // TODO: convert these methods using blas, e.g. dgbmv
-    vectorized_op!(mul_matrix_mut, mul_matrix, *);
-    vectorized_op!(div_matrix_mut, div_matrix, /);
+    vectorized_op!(mul_matrix_mut, mul_matrix, try_mul_matrix_mut, try_mul_matrix, *);
+    vectorized_op!(div_matrix_mut, div_matrix, try_div_matrix_mut, try_div_matrix, /);
 
     // == Matrix operations ==
 
     // Matrix multiply c = a * b using blas
     fn mmul_to(a: &DoubleMatrix, b: &DoubleMatrix, c: &mut DoubleMatrix) {
-        let (arows, acols) = a.shape();
-        let (brows, bcols) = b.shape();
+        Self::gemm(false, false, 1f64, a, b, 0f64, c);
+    }
+
+    // General matrix multiply c = alpha * op(a) * op(b) + beta * c using blas, where op(x) is
+    // either x or x^T depending on transpose flag. Transposed operands are not materialized, and
+    // when beta is 0, c does not need to be initialized.
+    pub fn gemm(
+        transa: bool,
+        transb: bool,
+        alpha: f64,
+        a: &DoubleMatrix,
+        b: &DoubleMatrix,
+        beta: f64,
+        c: &mut DoubleMatrix
+    ) {
+        unwrap_or_panic(Self::try_gemm(transa, transb, alpha, a, b, beta, c))
+    }
+
+    // General matrix multiply, returns error if dimensions of operands do not match
+    pub fn try_gemm(
+        transa: bool,
+        transb: bool,
+        alpha: f64,
+        a: &DoubleMatrix,
+        b: &DoubleMatrix,
+        beta: f64,
+        c: &mut DoubleMatrix
+    ) -> MatrixResult<()> {
+        // shapes of op(a) and op(b)
+        let (arows, acols) = if transa { (a.cols(), a.rows()) } else { a.shape() };
+        let (brows, bcols) = if transb { (b.cols(), b.rows()) } else { b.shape() };
         let (crows, ccols) = c.shape();
 
-        assert_eq!(acols, brows, "Input dimensions mismatch for multiplication.");
-        assert_eq!((crows, ccols), (arows, bcols), "Output dimensions mismatch for multiplication.");
+        ensure!(acols == brows, MatrixError::ShapeMismatch(
+            "Input dimensions mismatch for multiplication.".to_string()));
+        ensure!((crows, ccols) == (arows, bcols), MatrixError::ShapeMismatch(
+            "Output dimensions mismatch for multiplication.".to_string()));
 
         let m = arows as i32;
         let n = bcols as i32;
         let k = acols as i32;
 
-        let alpha = 1f64;
-        let beta = 0f64;
-
         unsafe {
             dgemm(
-                'N' as u8, // transa: u8,
-                'N' as u8, // transb: u8,
+                if transa { b'T' } else { b'N' }, // transa: u8,
+                if transb { b'T' } else { b'N' }, // transb: u8,
                 m, // m: i32,
                 n, // n: i32,
                 k, // k: i32,
                 alpha, // alpha: f64,
                 a.data(), // a: &[f64],
-                cmp::max(1, m), // lda: i32,
+                cmp::max(1, a.rows() as i32), // lda: i32,
                 b.data(), // b: &[f64],
-                cmp::max(1, k), // ldb: i32,
+                cmp::max(1, b.rows() as i32), // ldb: i32,
                 beta, // beta: f64,
                 c.data_mut(), // c: &mut [f64],
                 cmp::max(1, m) // ldc: i32
             );
         }
+        Ok(())
+    }
+
+    // Matrix multiply op(self) * op(b), where op(x) is either x or x^T depending on transpose flag
+    pub fn mmul_transpose(&self, b: &DoubleMatrix, transa: bool, transb: bool) -> DoubleMatrix {
+        unwrap_or_panic(self.try_mmul_transpose(b, transa, transb))
+    }
+
+    // Matrix multiply op(self) * op(b), returns error if dimensions do not match
+    pub fn try_mmul_transpose(
+        &self,
+        b: &DoubleMatrix,
+        transa: bool,
+        transb: bool
+    ) -> MatrixResult<DoubleMatrix> {
+        let rows = if transa { self.cols() } else { self.rows() };
+        let cols = if transb { b.rows() } else { b.cols() };
+        let mut res = DoubleMatrix::try_zeros(rows, cols)?;
+        Self::try_gemm(transa, transb, 1f64, self, b, 0f64, &mut res)?;
+        Ok(res)
     }
 
     // Matrix multiply
@@ -393,30 +1066,171 @@ impl DoubleMatrix {
         res
     }
 
+    // Matrix multiply, returns error if dimensions do not match
+    pub fn try_mmul(&self, b: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        self.try_mmul_transpose(b, false, false)
+    }
+
     // Matrix multiply in-place
     pub fn mmul_assign(&mut self, b: &DoubleMatrix) {
         *self = self.mmul(b);
     }
 
-    // Compute column sums
-    pub fn column_sums(&self) -> DoubleMatrix {
-        let mut vec = vec![0f64; self.cols()];
-        for c in 0..self.cols() {
-            for r in 0..self.rows() {
-                vec[c] += self.get(r, c);
+    // Symmetric rank-k update c = alpha * op(a) * op(a)^T + beta * c using blas dsyrk, where op(a)
+    // is a^T when trans is true and a otherwise. Only lower triangle is computed and then copied
+    // into upper triangle, c is assumed to be symmetric when beta is not 0.
+    pub fn syrk(trans: bool, alpha: f64, a: &DoubleMatrix, beta: f64, c: &mut DoubleMatrix) {
+        unwrap_or_panic(Self::try_syrk(trans, alpha, a, beta, c))
+    }
+
+    // Symmetric rank-k update, returns error if c is not of matching square shape
+    pub fn try_syrk(
+        trans: bool,
+        alpha: f64,
+        a: &DoubleMatrix,
+        beta: f64,
+        c: &mut DoubleMatrix
+    ) -> MatrixResult<()> {
+        // op(a) is n x k
+        let (n, k) = if trans { (a.cols(), a.rows()) } else { a.shape() };
+        ensure!(c.shape() == (n, n), MatrixError::ShapeMismatch(
+            "Output dimensions mismatch for multiplication.".to_string()));
+
+        unsafe {
+            dsyrk(
+                b'L', // uplo: u8,
+                if trans { b'T' } else { b'N' }, // trans: u8,
+                n as i32, // n: i32,
+                k as i32, // k: i32,
+                alpha, // alpha: f64,
+                a.data(), // a: &[f64],
+                cmp::max(1, a.rows() as i32), // lda: i32,
+                beta, // beta: f64,
+                c.data_mut(), // c: &mut [f64],
+                cmp::max(1, n as i32) // ldc: i32
+            );
+        }
+
+        // fill upper triangle from lower triangle
+        for j in 1..n {
+            for i in 0..j {
+                c.data[j * n + i] = c.data[i * n + j];
             }
         }
-        DoubleMatrix::new(1, self.cols(), vec)
+        Ok(())
     }
 
-    // Compute column mins
-    pub fn column_mins(&self) -> DoubleMatrix {
-        let mut vec = vec![NAN; self.cols()];
-        for c in 0..self.cols() {
-            for r in 0..self.rows() {
-                let e = self.get(r, c);
-                if vec[c].is_nan() || e < vec[c] {
-                    vec[c] = e;
+    // Compute Gram matrix a^T * a of column inner products using blas dsyrk
+    pub fn gram(&self) -> DoubleMatrix {
+        let mut c = DoubleMatrix::zeros(self.cols(), self.cols());
+        Self::syrk(true, 1f64, self, 0f64, &mut c);
+        c
+    }
+
+    // Compute Gram matrix a * a^T of row inner products using blas dsyrk
+    pub fn gram_rows(&self) -> DoubleMatrix {
+        let mut c = DoubleMatrix::zeros(self.rows(), self.rows());
+        Self::syrk(false, 1f64, self, 0f64, &mut c);
+        c
+    }
+
+    // Matrix-vector multiply y = a * x using blas dgemv, x is column vector
+    pub fn mv(&self, x: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_mv(x))
+    }
+
+    // Matrix-vector multiply, returns error if x is not column vector of matching length
+    pub fn try_mv(&self, x: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        Self::check_column_vector(x, self.cols())?;
+        let mut y = DoubleMatrix::try_zeros(self.rows(), 1)?;
+        self.matvec(x.data(), y.data_mut());
+        Ok(y)
+    }
+
+    // Transposed matrix-vector multiply y = a^T * x using blas dgemv, x is column vector
+    pub fn tmv(&self, x: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_tmv(x))
+    }
+
+    // Transposed matrix-vector multiply, returns error if x is not column vector of matching
+    // length
+    pub fn try_tmv(&self, x: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        Self::check_column_vector(x, self.rows())?;
+        let mut y = DoubleMatrix::try_zeros(self.cols(), 1)?;
+        self.rmatvec(x.data(), y.data_mut());
+        Ok(y)
+    }
+
+    // Outer product x * y^T of column vectors using blas dger
+    pub fn outer(x: &DoubleMatrix, y: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(Self::try_outer(x, y))
+    }
+
+    // Outer product of column vectors, returns error if x or y is not column vector
+    pub fn try_outer(x: &DoubleMatrix, y: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        Self::check_column_vector(x, x.rows())?;
+        Self::check_column_vector(y, y.rows())?;
+        let (m, n) = (x.rows(), y.rows());
+        let mut a = DoubleMatrix::try_zeros(m, n)?;
+        unsafe {
+            dger(m as i32, n as i32, 1f64, x.data(), 1i32, y.data(), 1i32, a.data_mut(),
+                cmp::max(1, m) as i32);
+        }
+        Ok(a)
+    }
+
+    #[inline]
+    fn check_column_vector(x: &DoubleMatrix, len: usize) -> MatrixResult<()> {
+        ensure!(x.shape() == (len, 1), MatrixError::ShapeMismatch(
+            format!("Invalid shape for column vector: {:?}.", x.shape())));
+        Ok(())
+    }
+
+    // Dot product of matrices of the same shape (sum of elementwise products) using blas ddot,
+    // e.g. inner product of vectors
+    pub fn dot(&self, other: &DoubleMatrix) -> f64 {
+        unwrap_or_panic(self.try_dot(other))
+    }
+
+    // Dot product of matrices, returns error if shapes do not match
+    pub fn try_dot(&self, other: &DoubleMatrix) -> MatrixResult<f64> {
+        check_shape!(self.shape(), other.shape());
+        let (x, y) = (self.data(), other.data());
+        Ok(unsafe { ddot(x.len() as i32, x, 1i32, y, 1i32) })
+    }
+
+    // Compute self = alpha * x + self in-place using blas daxpy
+    pub fn axpy(&mut self, alpha: f64, x: &DoubleMatrix) {
+        unwrap_or_panic(self.try_axpy(alpha, x))
+    }
+
+    // Compute self = alpha * x + self in-place, returns error if shapes do not match
+    pub fn try_axpy(&mut self, alpha: f64, x: &DoubleMatrix) -> MatrixResult<()> {
+        check_shape!(self.shape(), x.shape());
+        let y = self.data_mut();
+        unsafe { daxpy(y.len() as i32, alpha, x.data(), 1i32, y, 1i32); }
+        Ok(())
+    }
+
+    // Compute column sums
+    pub fn column_sums(&self) -> DoubleMatrix {
+        let mut vec = vec![0f64; self.cols()];
+        for c in 0..self.cols() {
+            for r in 0..self.rows() {
+                vec[c] += self.get(r, c);
+            }
+        }
+        DoubleMatrix::new(1, self.cols(), vec)
+    }
+
+    // Compute column mins
+    pub fn column_mins(&self) -> DoubleMatrix {
+        let mut vec = vec![f64::NAN; self.cols()];
+        for c in 0..self.cols() {
+            for r in 0..self.rows() {
+                let e = self.get(r, c);
+                if vec[c].is_nan() || e < vec[c] {
+                    vec[c] = e;
                 }
             }
         }
@@ -425,7 +1239,7 @@ impl DoubleMatrix {
 
     // Compute column maxs
     pub fn column_maxs(&self) -> DoubleMatrix {
-        let mut vec = vec![NAN; self.cols()];
+        let mut vec = vec![f64::NAN; self.cols()];
         for c in 0..self.cols() {
             for r in 0..self.rows() {
                 let e = self.get(r, c);
@@ -458,7 +1272,7 @@ impl DoubleMatrix {
 
     // Compute row mins
     pub fn row_mins(&self) -> DoubleMatrix {
-        let mut vec = vec![NAN; self.rows()];
+        let mut vec = vec![f64::NAN; self.rows()];
         for r in 0..self.rows() {
             for c in 0..self.cols() {
                 let e = self.get(r, c);
@@ -472,7 +1286,7 @@ impl DoubleMatrix {
 
     // Compute row maxs
     pub fn row_maxs(&self) -> DoubleMatrix {
-        let mut vec = vec![NAN; self.rows()];
+        let mut vec = vec![f64::NAN; self.rows()];
         for r in 0..self.rows() {
             for c in 0..self.cols() {
                 let e = self.get(r, c);
@@ -494,19 +1308,29 @@ impl DoubleMatrix {
 
     // Put (update) column vector in this matrix for a column index
     pub fn put_column(&mut self, col: usize, vector: &DoubleMatrix) {
-        assert_eq!(vector.shape(), (self.rows(), 1),
-            "Invalid shape for column vector: {:?}.", vector.shape());
-        assert!(col < self.cols(), "Invalid column index {} ({} columns).", col, self.cols());
+        unwrap_or_panic(self.try_put_column(col, vector))
+    }
+
+    // Put column vector, returns error if vector has invalid shape or index is out of bounds
+    pub fn try_put_column(&mut self, col: usize, vector: &DoubleMatrix) -> MatrixResult<()> {
+        Self::check_column_vector(vector, self.rows())?;
+        self.check_column_index(col)?;
         let start = self.m2v(0, col);
         let end = self.m2v(self.rows() - 1, col);
         let dx = vector.data();
         let mut dy = &mut self.data[start..end];
         dcopy![dx, dy];
+        Ok(())
     }
 
     // Get column for index col from this matrix as column vector
     pub fn get_column(&self, col: usize) -> DoubleMatrix {
-        assert!(col < self.cols(), "Invalid column index {} ({} columns).", col, self.cols());
+        unwrap_or_panic(self.try_get_column(col))
+    }
+
+    // Get column as column vector, returns error if index is out of bounds
+    pub fn try_get_column(&self, col: usize) -> MatrixResult<DoubleMatrix> {
+        self.check_column_index(col)?;
         let mut vector = DoubleMatrix::zeros(self.rows(), 1);
         {
             let start = self.m2v(0, col);
@@ -515,32 +1339,57 @@ impl DoubleMatrix {
             let mut dy = &mut vector.data;
             dcopy![dx, dy];
         }
-        vector
+        Ok(vector)
     }
 
     // Put (update) row vector in this matrix for a row index
     pub fn put_row(&mut self, row: usize, vector: &DoubleMatrix) {
-        assert_eq!(vector.shape(), (1, self.cols()),
-            "Invalid shape for row vector: {:?}.", vector.shape());
-        assert!(row < self.rows(), "Invalid row index {} ({} rows).", row, self.rows());
+        unwrap_or_panic(self.try_put_row(row, vector))
+    }
+
+    // Put row vector, returns error if vector has invalid shape or index is out of bounds
+    pub fn try_put_row(&mut self, row: usize, vector: &DoubleMatrix) -> MatrixResult<()> {
+        ensure!(vector.shape() == (1, self.cols()), MatrixError::ShapeMismatch(
+            format!("Invalid shape for row vector: {:?}.", vector.shape())));
+        self.check_row_index(row)?;
         for col in 0..self.cols() {
             self.put(row, col, vector.get(0, col));
         }
+        Ok(())
     }
 
     // Get row for index row from this matrix as row vector
     pub fn get_row(&self, row: usize) -> DoubleMatrix {
-        assert!(row < self.rows(), "Invalid row index {} ({} rows).", row, self.rows());
+        unwrap_or_panic(self.try_get_row(row))
+    }
+
+    // Get row as row vector, returns error if index is out of bounds
+    pub fn try_get_row(&self, row: usize) -> MatrixResult<DoubleMatrix> {
+        self.check_row_index(row)?;
         let mut vector = vec![0f64; self.cols()];
         for col in 0..self.cols() {
             vector[col] = self.get(row, col);
         }
-        DoubleMatrix::new(1, self.cols(), vector)
+        Ok(DoubleMatrix::new(1, self.cols(), vector))
+    }
+
+    #[inline]
+    fn check_column_index(&self, col: usize) -> MatrixResult<()> {
+        ensure!(col < self.cols(), MatrixError::IndexOutOfBounds(
+            format!("Invalid column index {} ({} columns).", col, self.cols())));
+        Ok(())
+    }
+
+    #[inline]
+    fn check_row_index(&self, row: usize) -> MatrixResult<()> {
+        ensure!(row < self.rows(), MatrixError::IndexOutOfBounds(
+            format!("Invalid row index {} ({} rows).", row, self.rows())));
+        Ok(())
     }
 
     // Find min element in matrix
     pub fn min(&self) -> f64 {
-        let mut min = NAN;
+        let mut min = f64::NAN;
         for &elem in self.data() {
             if min.is_nan() || elem < min {
                 min = elem;
@@ -551,7 +1400,7 @@ impl DoubleMatrix {
 
     // Find max element in matrix
     pub fn max(&self) -> f64 {
-        let mut max = NAN;
+        let mut max = f64::NAN;
         for &elem in self.data() {
             if max.is_nan() || elem > max {
                 max = elem;
@@ -597,7 +1446,13 @@ impl DoubleMatrix {
 
     // Transpose matrix in place
     pub fn transpose_mut(&mut self) {
-        assert_eq!(self.rows(), self.cols(), "Unable to transpose a non-square matrix in-place.");
+        unwrap_or_panic(self.try_transpose_mut())
+    }
+
+    // Transpose matrix in place, returns error if matrix is not square
+    pub fn try_transpose_mut(&mut self) -> MatrixResult<()> {
+        ensure!(self.rows() == self.cols(), MatrixError::ShapeMismatch(
+            "Unable to transpose a non-square matrix in-place.".to_string()));
         let dim = self.rows();
         for i in 1 .. dim {
             for j in 0 .. i {
@@ -606,16 +1461,23 @@ impl DoubleMatrix {
                 self.data.swap(a, b);
             }
         }
+        Ok(())
     }
 
     // Return diagonal as column vector
     pub fn diag(&self) -> DoubleMatrix {
-        assert_eq!(self.rows(), self.cols(), "Unable to get the diagonal of a non-square matrix.");
+        unwrap_or_panic(self.try_diag())
+    }
+
+    // Return diagonal as column vector, returns error if matrix is not square
+    pub fn try_diag(&self) -> MatrixResult<DoubleMatrix> {
+        ensure!(self.rows() == self.cols(), MatrixError::ShapeMismatch(
+            "Unable to get the diagonal of a non-square matrix.".to_string()));
         let mut diag = DoubleMatrix::zeros(self.rows(), 1);
         for i in 0..self.rows() {
             diag.put(i, 0, self.get(i, i));
         }
-        diag
+        Ok(diag)
     }
 
     // Return matrix of absolute values
@@ -627,9 +1489,24 @@ impl DoubleMatrix {
         DoubleMatrix::new(self.rows(), self.cols(), vec)
     }
 
+    // Whether or not matrix has fewer rows than columns. Lanczos SVD routines factor transpose of
+    // such matrix, which is tall, by swapping `transa` in APROD, and swap left and right singular
+    // vectors, see `transposed_dense_matmul`. Dense SVD routines (`full_svd`, `svd`) do not check
+    // shape: DGESDD and DGESVDX already handle M < N, matrix is reduced with LQ factorization
+    // first when N is much larger than M, so transposed copy would only double memory.
+    #[inline]
+    fn is_wide(&self) -> bool {
+        self.rows < self.cols
+    }
+
     // Compute the singular value decomposition (SVD) of a real M-by-N matrix, also computing the
     // left and right singular vectors, for which it uses a divide-and-conquer algorithm.
     pub fn full_svd(&self) -> SVD {
+        unwrap_or_panic(self.try_full_svd())
+    }
+
+    // Compute full SVD, returns error if GESDD did not converge
+    pub fn try_full_svd(&self) -> MatrixResult<SVD> {
         let jobz = 'A';
         let (rows, cols) = self.shape();
         // need to copy content of a, since it can be modified, have we decided to change mode
@@ -655,11 +1532,16 @@ impl DoubleMatrix {
         let mut v = DoubleMatrix::new(vtrows, vtcols, vt);
         v.transpose_mut();
 
-        SVD { u: Some(u), s: s, v: Some(v) }
+        Ok(SVD { u: Some(u), s, v: Some(v) })
     }
 
     // Compute all (up to epsilon) singular values for this matrix
     pub fn singular_values(&self) -> DoubleMatrix {
+        unwrap_or_panic(self.try_singular_values())
+    }
+
+    // Compute all singular values, returns error if GESDD did not converge
+    pub fn try_singular_values(&self) -> MatrixResult<DoubleMatrix> {
         let jobz = 'N';
         let (rows, cols) = self.shape();
         // need to copy content of a, since it can be modified, have we decided to change mode
@@ -677,32 +1559,133 @@ impl DoubleMatrix {
 
         dgesdd_op!(jobz, a, rows, cols, u, urows, s, vt, vtrows);
 
-        DoubleMatrix::new(srows, scols, s)
+        Ok(DoubleMatrix::new(srows, scols, s))
+    }
+
+    // Compute thin SVD using divide-and-conquer algorithm, left and right singular vectors have
+    // min(rows, cols) columns; used for randomized svd, pseudo-inverse and fundamental subspaces.
+    fn thin_svd(&self) -> MatrixResult<SVD> {
+        let jobz = 'S';
+        let (rows, cols) = self.shape();
+        let mut a = dcopy![self.data()];
+        let srows = cmp::min(rows, cols);
+        let mut s = vec![0f64; srows];
+        let urows = rows;
+        let mut u = vec![0f64; urows * srows];
+        let vtrows = srows;
+        let mut vt = vec![0f64; vtrows * cols];
+
+        dgesdd_op!(jobz, a, rows, cols, u, urows, s, vt, vtrows);
+
+        let u = DoubleMatrix::new(urows, srows, u);
+        let s = DoubleMatrix::new(srows, 1, s);
+        let v = DoubleMatrix::new(vtrows, cols, vt).transpose();
+        Ok(SVD { u: Some(u), s, v: Some(v) })
+    }
+
+    // Orthonormal basis for the range of this matrix, assumes rows >= cols
+    fn orthonormal_basis(&self) -> MatrixResult<DoubleMatrix> {
+        Ok(self.thin_svd()?.u.unwrap())
+    }
+
+    // Keep first k columns of the matrix, data is in column-major order
+    fn truncate_columns(mut self, k: usize) -> DoubleMatrix {
+        self.data.truncate(self.rows * k);
+        self.cols = k;
+        self
     }
 
     // Experimental svd for top k singular values.
-    // Currenty computes both left and right singular vectors, this behaviour can be changed later,
-    // to allow choice of left or right or none to speed up computation.
+    // Computes both left and right singular vectors, see `svd_job` to select singular vectors.
     //
     // Based on DGESVDX that uses an eigenvalue problem for obtaining the SVD, which
     // allows for the computation of a subset of singular values and vectors.
     // See Lapack/DBDSVDX for details.
     pub fn svd(&self, k: usize) -> SVD {
+        self.svd_job(k, SvdJob::Both)
+    }
+
+    // Experimental svd for top k singular values, returns error instead of panicking
+    pub fn try_svd(&self, k: usize) -> MatrixResult<SVD> {
+        self.try_svd_job(k, SvdJob::Both)
+    }
+
+    // Experimental svd for top k singular values, computes only singular vectors selected by job,
+    // vectors that are not computed are set to None.
+    pub fn svd_job(&self, k: usize, job: SvdJob) -> SVD {
+        unwrap_or_panic(self.try_svd_job(k, job))
+    }
+
+    // Experimental svd for top k singular values with job, returns error for invalid k or when
+    // GESVDX did not converge
+    pub fn try_svd_job(&self, k: usize, job: SvdJob) -> MatrixResult<SVD> {
+        let (rows, cols) = self.shape();
+        Self::check_num_values(k, cmp::min(rows, cols))?;
+        let svd = self.try_svd_range(SvdRange::Index(0, k), job)?;
+        // info code is the number of singular values found
+        ensure!(svd.s.rows() == k, MatrixError::NotConverged(format!(
+            "GESVDX: {} (ns[0]) != {} (k), truncation is not supported.", svd.s.rows(), k),
+            svd.s.rows() as i32));
+        Ok(svd)
+    }
+
+    #[inline]
+    fn check_num_values(k: usize, max: usize) -> MatrixResult<()> {
+        ensure!(k >= 1 && k <= max, MatrixError::InvalidArgument(
+            format!("Invalid number of singular values: {}.", k)));
+        Ok(())
+    }
+
+    // Experimental svd for a subset of singular values selected by range, computes only singular
+    // vectors selected by job. Returns however many singular triplets were found, which can be
+    // fewer than requested (or none) for value range.
+    pub fn svd_range(&self, range: SvdRange, job: SvdJob) -> SVD {
+        unwrap_or_panic(self.try_svd_range(range, job))
+    }
+
+    // Experimental svd for a subset of singular values, returns error for invalid range or when
+    // GESVDX did not converge
+    pub fn try_svd_range(&self, range: SvdRange, job: SvdJob) -> MatrixResult<SVD> {
         let (rows, cols) = self.shape();
-        assert!(k >= 1 && k <= cmp::min(rows, cols), "Invalid number of singular values: {}.", k);
+        let minmn = cmp::min(rows, cols);
+        // range parameters for DGESVDX, il and iu are 1-based indices, only one pair is referenced
+        let (rng, vl, vu, il, iu, maxns) = match range {
+            SvdRange::Index(start, end) => {
+                ensure!(start < end && end <= minmn, MatrixError::InvalidArgument(
+                    format!("Invalid range of singular values: [{}, {}) for {} values.", start,
+                        end, minmn)));
+                ('I', 0f64, 0f64, start + 1, end, end - start)
+            },
+            SvdRange::Value(vl, vu) => {
+                ensure!(vl >= 0f64 && vl < vu, MatrixError::InvalidArgument(
+                    format!("Invalid interval of singular values: ({}, {}].", vl, vu)));
+                // Frobenius norm bounds the largest singular value, replace infinite upper bound
+                let vu = if vu.is_infinite() { 2f64 * self.norm2() + 1f64 } else { vu };
+                if vl >= vu {
+                    // interval is above the largest singular value, no singular triplets found
+                    return Ok(SVD {
+                        u: if job.left() { Some(DoubleMatrix::new(rows, 0, vec![])) } else { None },
+                        s: DoubleMatrix::new(0, 1, vec![]),
+                        v: if job.right() { Some(DoubleMatrix::new(cols, 0, vec![])) } else { None }
+                    });
+                }
+                ('V', vl, vu, 1, minmn, minmn)
+            }
+        };
 
         let mut a = dcopy![self.data()];
         // singular values vector
         let mut ns = vec![0i32; 1]; // vector to contain number of singluar values found
-        let (srows, scols) = (cmp::min(rows, cols), 1);
-        let mut s = vec![0f64; srows * scols];
-        // left singular vectors
-        let (urows, ucols) = (rows, k);
+        let mut s = vec![0f64; minmn];
+        // left singular vectors, not referenced when jobu = 'N'
+        let jobu = if job.left() { 'V' } else { 'N' };
+        let (urows, ucols) = if job.left() { (rows, maxns) } else { (1, 0) };
         let mut u = vec![0f64; urows * ucols];
-        // right singular vectors
-        let (vtrows, vtcols) = (k, cols);
+        // right singular vectors, not referenced when jobvt = 'N'
+        let jobvt = if job.right() { 'V' } else { 'N' };
+        let (vtrows, vtcols) = if job.right() { (maxns, cols) } else { (1, 0) };
         let mut vt = vec![0f64; vtrows * vtcols];
-        let mut iwork = vec![0i32; 12 * cmp::min(rows, cols)];
+        let mut iwork = vec![0i32; 12 * minmn];
         let mut info = 0i32;
 
         // estimate size of lwork
@@ -711,31 +1694,31 @@ impl DoubleMatrix {
 
         unsafe {
             dgesvdx(
-                'V' as u8, // jobu: u8,
-                'V' as u8, // jobvt: u8,
-                'I' as u8, // range: u8,
+                jobu as u8, // jobu: u8,
+                jobvt as u8, // jobvt: u8,
+                rng as u8, // range: u8,
                 rows as i32, // m: i32,
                 cols as i32, // n: i32,
-                &mut vec![], // a: &mut [f64],
+                &mut [], // a: &mut [f64],
                 cmp::max(1, rows) as i32, // lda: i32,
-                0f64, // vl: f64,
-                0f64, // vu: f64,
-                1i32, // il: i32,
-                k as i32, // iu: i32,
+                vl, // vl: f64,
+                vu, // vu: f64,
+                il as i32, // il: i32,
+                iu as i32, // iu: i32,
                 &mut ns, // ns: &mut [i32],
-                &mut vec![], // s: &mut [f64],
-                &mut vec![], // u: &mut [f64],
+                &mut [], // s: &mut [f64],
+                &mut [], // u: &mut [f64],
                 cmp::max(1, urows) as i32, // ldu: i32,
-                &mut vec![], // vt: &mut [f64],
+                &mut [], // vt: &mut [f64],
                 cmp::max(1, vtrows) as i32, // ldvt: i32,
                 &mut work, // work: &mut [f64],
                 lwork, // lwork: i32,
-                &mut vec![], // iwork: &mut [i32],
+                &mut [], // iwork: &mut [i32],
                 &mut info, // info: &mut i32
             );
         }
 
-        assert!(info == 0, "Workspace query failed to execute with code {}.", info);
+        check_workspace!(info);
 
         // additional workspace data structures after adjustment
         let lwork = work[0] as usize;
@@ -743,17 +1726,17 @@ impl DoubleMatrix {
 
         unsafe {
             dgesvdx(
-                'V' as u8, // jobu: u8,
-                'V' as u8, // jobvt: u8,
-                'I' as u8, // range: u8,
+                jobu as u8, // jobu: u8,
+                jobvt as u8, // jobvt: u8,
+                rng as u8, // range: u8,
                 rows as i32, // m: i32,
                 cols as i32, // n: i32,
                 &mut a, // a: &mut [f64],
                 cmp::max(1, rows) as i32, // lda: i32,
-                0f64, // vl: f64,
-                0f64, // vu: f64,
-                1i32, // il: i32,
-                k as i32, // iu: i32,
+                vl, // vl: f64,
+                vu, // vu: f64,
+                il as i32, // il: i32,
+                iu as i32, // iu: i32,
                 &mut ns, // ns: &mut [i32],
                 &mut s, // s: &mut [f64],
                 &mut u, // u: &mut [f64],
@@ -767,903 +1750,3457 @@ impl DoubleMatrix {
             );
         }
 
-        // this is strict check; when info is negative, then ith parameter has illegal value
-        assert!(info == 0, "GESVDX did not converge, {}.", info);
-        assert!(ns[0] == k as i32,
-            "GESVDX: {} (ns[0]) != {} (k), truncation is not supported.", ns[0], k);
-
-        let u = DoubleMatrix::new(urows, ucols, u);
-        s.truncate(k); // do not call dcopy on s, it is normally small (< 100)
-        let s = DoubleMatrix::new(k, 1, s);
-        // v is returned as vt, transpose by copy, because it is not square
-        let v = DoubleMatrix::new(vtrows, vtcols, vt).transpose();
-        SVD { u: Some(u), s: s, v: Some(v) }
+        // when info is negative, then ith parameter has illegal value
+        ensure!(info >= 0, MatrixError::InvalidArgument(
+            format!("GESVDX failed to execute with code {}.", info)));
+        ensure!(info == 0,
+            MatrixError::NotConverged(format!("GESVDX did not converge, {}.", info), info));
+        let ns = cmp::min(cmp::max(ns[0], 0) as usize, maxns);
+
+        // u is stored column-major with ldu = rows, first ns columns hold singular vectors
+        let u = if job.left() {
+            u.truncate(rows * ns);
+            Some(DoubleMatrix::new(rows, ns, u))
+        } else {
+            None
+        };
+        s.truncate(ns); // do not call dcopy on s, it is normally small (< 100)
+        let s = DoubleMatrix::new(ns, 1, s);
+        // v is returned as vt with ldvt = maxns, copy first ns rows of vt as columns of v
+        let v = if job.right() {
+            let mut v = vec![0f64; cols * ns];
+            for i in 0..ns {
+                for j in 0..cols {
+                    v[i * cols + j] = vt[j * vtrows + i];
+                }
+            }
+            Some(DoubleMatrix::new(cols, ns, v))
+        } else {
+            None
+        };
+        Ok(SVD { u, s, v })
     }
 
     // Experimental svd for top k singular values.
-    // Currently computes both left and right singular vectors, this behaviour can be changed to
-    // allow faster computation. Also would be good to expose some options, like tolerance and max
-    // number of iterations.
+    // Computes both left and right singular vectors, use `lansvd_with` and job in options to
+    // select singular vectors.
     //
     // Based on DLANSVD_IRL that computes the leading singular triplets of a large and sparse matrix
     // A by implicitly restarted Lanczos bidiagonalization with partial reorthogonalization.
     // Note that current method uses A as dense matrix, which is influenced by using dense_matmul,
     // based on BLAS dgemv.
+    // Wide matrix is factored as transpose without copying, see `is_wide`.
     pub fn lansvd(&self, k: usize) -> SVD {
-        let (rows, cols) = self.shape();
-        let lanmax = cmp::min(rows, cols);
-        assert!(k >= 1 && k <= lanmax, "Invalid number of singular values: {}.", k);
-
-        // number of desired singular triplets.
-        let neig = cmp::min(k, lanmax);
-        let kmax = cmp::min(7 * neig, lanmax + 1);
-        // dimension of Krylov subspace
-        let dim = kmax;
-        // number of shift per restart
-        let p = dim - neig;
-        // maximum number of restarts (it looks like dlansvd_irl forces this number of iterations)
-        // current value works well enough
-        let maxiter = 3i32; // original value was 10
-        // desired relative accuracy of computed singular values
-        let tolin = 1e-8;
+        self.lansvd_with(k, &LanczosOptions::new())
+    }
 
-        // left singular vectors
-        let (urows, ucols) = (rows, kmax + 1);
-        let mut u = vec![0f64; urows * ucols];
+    // Experimental svd for top k singular values with provided options, e.g. tolerance and max
+    // number of restarts, see `lansvd` for details. When options select smallest singular values,
+    // they are returned in descending order similar to the largest ones.
+    pub fn lansvd_with(&self, k: usize, options: &LanczosOptions) -> SVD {
+        unwrap_or_panic(self.try_lansvd_with(k, options))
+    }
 
-        let mut sigma = vec![0f64; kmax];
-        let mut bnd = vec![0f64; kmax];
+    // Experimental svd for top k singular values with provided options, returns error for
+    // invalid options or when not all singular triplets converged
+    pub fn try_lansvd_with(&self, k: usize, options: &LanczosOptions) -> MatrixResult<SVD> {
+        self.try_lansvd_result(k, options)?.try_into_svd()
+    }
 
-        // right singular vectors
-        let (vrows, vcols) = (kmax, cols);
-        let mut v = vec![0f64; vrows * vcols];
+    // Experimental svd for top k singular values that returns error bounds and convergence
+    // diagnostics instead of failing when not all singular triplets converged.
+    pub fn lansvd_result(&self, k: usize, options: &LanczosOptions) -> LanczosSvd {
+        unwrap_or_panic(self.try_lansvd_result(k, options))
+    }
 
-        // size of work
-        let lwork = rows + cols + 14 * kmax + 8 * kmax * kmax + 32 * rows + 9;
-        // work buffer
-        let mut work = vec![0f64; lwork];
+    // Experimental svd for top k singular values with convergence diagnostics, returns error for
+    // invalid options
+    pub fn try_lansvd_result(
+        &self,
+        k: usize,
+        options: &LanczosOptions
+    ) -> MatrixResult<LanczosSvd> {
+        let (rows, cols) = self.shape();
+        // dparm is *const f64, we do not change original matrix
+        // iparm is used for passing data to the APROD function, not used for dense matrices
+        let data = self.data().as_ptr();
+        if self.is_wide() {
+            let options = options.clone().job(options.job.transpose());
+            let aprod: PROPACK_EXTERN = Some(transposed_dense_matmul);
+            let mut res = unsafe {
+                try_lansvd_op(cols, rows, k, &options, aprod, data, ptr::null())?
+            };
+            res.svd = res.svd.transpose();
+            return Ok(res);
+        }
+        unsafe { try_lansvd_op(rows, cols, k, options, Some(dense_matmul), data, ptr::null()) }
+    }
 
-        // size of iwork
-        let liwork = 8 * kmax;
-        let mut iwork = vec![0i32; liwork];
+    // Experimental svd for top k singular values with at most kmax Lanczos steps.
+    //
+    // Based on DLANSVD that computes the leading singular triplets of a large and sparse matrix
+    // by Lanczos bidiagonalization with partial reorthogonalization, without restarts; Krylov
+    // subspace grows until k singular values converge or kmax dimension is reached.
+    pub fn lansvd_pro(&self, k: usize, kmax: usize) -> SVD {
+        unwrap_or_panic(self.try_lansvd_pro(k, kmax))
+    }
 
-        // doption
-        let doption = vec![
-            // level of orthogonality to maintain among Lanczos vectors
-            EPSILON.sqrt(),
-            // during reorthogonalization, all vectors with with components larger than this value
-            // along the latest Lanczos vector c will be purged
-            EPSILON.powf(3.0 / 4.0),
-            // estimate of || A ||
-            0f64,
-            // smallest relgap between any shift the smallest requested Ritz value
-            0.002
-        ];
-
-        let ioption = vec![
-            // reorthogonalization is done using iterated modified Gram-Schmidt
-            0i32,
-            // extended local orthogonality is enforced among u_{k}, u_{k+1} and v_{k} and v_{k+1}
-            // respectively
-            1i32
-        ];
-
-        // status info
-        let mut info = 0i32;
+    // Experimental svd for top k singular values with at most kmax Lanczos steps, returns error
+    // for invalid arguments or when DLANSVD did not converge
+    pub fn try_lansvd_pro(&self, k: usize, kmax: usize) -> MatrixResult<SVD> {
+        let (rows, cols) = self.shape();
+        let data = self.data().as_ptr();
+        if self.is_wide() {
+            let aprod: PROPACK_EXTERN = Some(transposed_dense_matmul);
+            let svd = unsafe { try_lansvd_pro_op(cols, rows, k, kmax, aprod, data, ptr::null())? };
+            return Ok(svd.transpose());
+        }
+        unsafe { try_lansvd_pro_op(rows, cols, k, kmax, Some(dense_matmul), data, ptr::null()) }
+    }
 
-        // dparm is *const f64, we do not change original matrix
-        let dparm = self.data();
-        // array used for passing data to the APROD function, not used for dense matrices
-        let iparm = vec![];
+    // Experimental randomized svd for top k singular values, uses default oversampling of 10
+    // columns and 2 power iterations, see `rsvd_with` for details.
+    pub fn rsvd(&self, k: usize) -> SVD {
+        self.rsvd_with(k, 10, 2)
+    }
 
-        unsafe {
-            dlansvd_irl(
-                'L' as u8,
-                'Y' as u8,
-                'Y' as u8,
-                rows as i32,
-                cols as i32,
-                dim as i32,
-                p as i32,
-                neig as i32,
-                maxiter,
-                &mut u,
-                cmp::max(1, urows) as i32,
-                &mut sigma,
-                &mut bnd,
-                &mut v,
-                cmp::max(1, vcols) as i32,
-                tolin,
-                &mut work,
-                lwork as i32,
-                &mut iwork,
-                liwork as i32,
-                &doption,
-                &ioption,
-                &mut info,
-                dparm,
-                &iparm
-            );
+    // Experimental randomized svd for top k singular values with provided oversampling and number
+    // of power iterations.
+    //
+    // Based on randomized range finder by Halko, Martinsson and Tropp: orthonormal basis Q of the
+    // range of A * Omega, where Omega is a random matrix with k + oversampling columns, is refined
+    // with power iterations (A * A^T) * Q, and the SVD of small projected matrix Q^T * A is used to
+    // approximate the SVD of A. Results differ slightly between calls for slow decaying spectrum.
+    pub fn rsvd_with(&self, k: usize, oversampling: usize, power_iters: usize) -> SVD {
+        unwrap_or_panic(self.try_rsvd_with(k, oversampling, power_iters))
+    }
+
+    // Experimental randomized svd for top k singular values, returns error for invalid k or when
+    // SVD of projected problem did not converge
+    pub fn try_rsvd_with(
+        &self,
+        k: usize,
+        oversampling: usize,
+        power_iters: usize
+    ) -> MatrixResult<SVD> {
+        let (rows, cols) = self.shape();
+        Self::check_num_values(k, cmp::min(rows, cols))?;
+        let l = cmp::min(k + oversampling, cmp::min(rows, cols));
+
+        // random values are centered around 0, which improves the range approximation
+        let omega = DoubleMatrix::new_random(cols, l).sub_scalar(0.5);
+        let mut q = self.mmul(&omega).orthonormal_basis()?;
+        // orthonormalize every step to avoid losing precision for small singular values
+        for _ in 0..power_iters {
+            let z = self.mmul_transpose(&q, true, false).orthonormal_basis()?;
+            q = self.mmul(&z).orthonormal_basis()?;
         }
 
-        if info == -1 {
-            panic!("DLANSVD_IRL, K singular triplets did not converge within KMAX iterations.");
-        } else if info != 0 {
-            panic!("DLANSVD_IRL, an invariant subspace of dimension J was found, {}.", info);
+        // l x cols projected problem
+        let b = q.mmul_transpose(self, true, false);
+        let svd = b.thin_svd()?;
+        let u = q.mmul(&svd.u.unwrap().truncate_columns(k));
+        let mut s = svd.s.data;
+        s.truncate(k);
+        let s = DoubleMatrix::new(k, 1, s);
+        let v = svd.v.unwrap().truncate_columns(k);
+        Ok(SVD { u: Some(u), s, v: Some(v) })
+    }
+
+    // == SVD-based quantities ==
+
+    // Resolve tolerance for singular values, when tol is negative, default tolerance
+    // max(rows, cols) * s(0) * eps is used, where s(0) is the largest singular value
+    fn svd_tolerance(&self, s: &DoubleMatrix, tol: f64) -> f64 {
+        if tol >= 0f64 {
+            return tol;
         }
-        // at this point info == 0, K singular triplets were computed succesfully
+        let smax = if s.rows() > 0 { s.get(0, 0) } else { 0f64 };
+        cmp::max(self.rows, self.cols) as f64 * smax * f64::EPSILON
+    }
 
-        // truncate u to (rows, neig)
-        let (urows, ucols) = (rows, neig);
-        u.truncate(urows * ucols);
-        let u = DoubleMatrix::new(urows, ucols, u);
+    // Number of singular values (in descending order) greater than tolerance
+    #[inline]
+    fn count_above(s: &DoubleMatrix, tol: f64) -> usize {
+        s.data().iter().take_while(|&&value| value > tol).count()
+    }
 
-        // truncate sigma to neig
-        sigma.truncate(neig);
-        let s = DoubleMatrix::new(neig, 1, sigma);
+    // Compute numerical rank of the matrix as number of singular values greater than tol, when
+    // tol is negative, default tolerance is used, see `svd_tolerance`
+    pub fn rank(&self, tol: f64) -> usize {
+        unwrap_or_panic(self.try_rank(tol))
+    }
 
-        // truncate v to (neig, cols)
-        // note that v comes already transposed (not as v^T), so we just swap rows and cols
-        let (vrows, vcols) = (cols, neig);
-        v.truncate(vrows * vcols);
-        let v = DoubleMatrix::new(vrows, vcols, v);
+    // Compute numerical rank, returns error if SVD did not converge
+    pub fn try_rank(&self, tol: f64) -> MatrixResult<usize> {
+        let s = self.try_singular_values()?;
+        let tol = self.svd_tolerance(&s, tol);
+        Ok(Self::count_above(&s, tol))
+    }
 
-        SVD { u: Some(u), s: s, v: Some(v) }
+    // Compute 2-norm condition number as ratio of the largest and the smallest singular values,
+    // returns infinity for rank-deficient matrix
+    pub fn cond(&self) -> f64 {
+        unwrap_or_panic(self.try_cond())
     }
-}
 
-impl Clone for DoubleMatrix {
-    fn clone(&self) -> Self {
-        DoubleMatrix::new(self.rows, self.cols, dcopy![self.data()])
+    // Compute 2-norm condition number, returns error for empty matrix or if SVD did not converge
+    pub fn try_cond(&self) -> MatrixResult<f64> {
+        let s = self.try_singular_values()?;
+        ensure!(s.rows() > 0, MatrixError::InvalidArgument(
+            "Condition number is not defined for empty matrix.".to_string()));
+        let (smax, smin) = (s.get(0, 0), s.get(s.rows() - 1, 0));
+        Ok(if smin == 0f64 { f64::INFINITY } else { smax / smin })
     }
-}
 
-impl Display for DoubleMatrix {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        fn val_width(val: f64, f: &mut Formatter) -> usize {
-            match f.precision() {
-                Some(precision) => format!("{:.1$}", val, precision).chars().count(),
-                None => format!("{}", val).chars().count()
+    // Compute Moore-Penrose pseudo-inverse using thin SVD, singular values s(i) <= rcond * s(0)
+    // are treated as zero; when rcond is negative, default tolerance is used, see
+    // `svd_tolerance`. Returns N-by-M matrix.
+    pub fn pinv(&self, rcond: f64) -> DoubleMatrix {
+        unwrap_or_panic(self.try_pinv(rcond))
+    }
+
+    // Compute pseudo-inverse, returns error if SVD did not converge
+    pub fn try_pinv(&self, rcond: f64) -> MatrixResult<DoubleMatrix> {
+        let svd = self.thin_svd()?;
+        let tol = if rcond >= 0f64 && svd.s.rows() > 0 {
+            rcond * svd.s.get(0, 0)
+        } else {
+            self.svd_tolerance(&svd.s, -1f64)
+        };
+        let r = Self::count_above(&svd.s, tol);
+        let u = svd.u.unwrap().truncate_columns(r);
+        // pinv = V * diag(1 / s) * U^T, scale columns of V by reciprocals of singular values
+        let mut v = svd.v.unwrap().truncate_columns(r);
+        let rows = v.rows();
+        for j in 0..r {
+            let scale = 1f64 / svd.s.get(j, 0);
+            for value in &mut v.data[j * rows..(j + 1) * rows] {
+                *value *= scale;
             }
         }
+        Ok(v.mmul(&u.transpose()))
+    }
 
-        let (rows, cols) = self.shape();
+    // Compute orthonormal basis for the null space of the matrix as right singular vectors that
+    // correspond to singular values less than or equal to tol, see `rank` for tolerance.
+    // Returns N-by-(N - rank) matrix.
+    pub fn null_space(&self, tol: f64) -> DoubleMatrix {
+        unwrap_or_panic(self.try_null_space(tol))
+    }
 
-        if rows == 0 || cols == 0 {
-            return write!(f, "[ ]");
-        }
+    // Compute orthonormal basis for the null space, returns error if SVD did not converge
+    pub fn try_null_space(&self, tol: f64) -> MatrixResult<DoubleMatrix> {
+        // thin SVD already has all N right singular vectors unless matrix is wide
+        let svd = if self.is_wide() { self.try_full_svd()? } else { self.thin_svd()? };
+        let tol = self.svd_tolerance(&svd.s, tol);
+        let r = Self::count_above(&svd.s, tol);
+        let v = svd.v.unwrap();
+        let n = v.rows();
+        Ok(DoubleMatrix::new(n, n - r, v.data[r * n..].to_vec()))
+    }
 
-        let mut max_length_with_space = 0;
-        let mut col_lengths = vec![0; cols];
+    // Compute orthonormal basis for the range (column space) of the matrix as left singular
+    // vectors that correspond to singular values greater than tol, see `rank` for tolerance.
+    // Returns M-by-rank matrix.
+    pub fn range_space(&self, tol: f64) -> DoubleMatrix {
+        unwrap_or_panic(self.try_range_space(tol))
+    }
 
-        for j in 0..cols {
-            for i in 0..rows {
-                let idx = self.m2v(i, j);
-                col_lengths[j] = cmp::max(col_lengths[j], val_width(self.data[idx], f));
-            }
-            max_length_with_space += col_lengths[j] + 1;
-        }
+    // Compute orthonormal basis for the range, returns error if SVD did not converge
+    pub fn try_range_space(&self, tol: f64) -> MatrixResult<DoubleMatrix> {
+        let svd = self.thin_svd()?;
+        let tol = self.svd_tolerance(&svd.s, tol);
+        let r = Self::count_above(&svd.s, tol);
+        Ok(svd.u.unwrap().truncate_columns(r))
+    }
 
-        writeln!(f, "")?;
-        writeln!(f, "  . {:>width$} .", "", width = max_length_with_space - 1)?;
+    // == Linear systems ==
 
-        for i in 0..rows {
-            write!(f, "  |")?;
-            for j in 0..cols {
-                let idx = self.m2v(i, j);
-                let pad = col_lengths[j] - val_width(self.data[idx], f);
-                write!(f, " {:>thepad$}", "", thepad = pad)?;
-                match f.precision() {
-                    Some(precision) => write!(f, "{:.1$}", self.data[idx], precision)?,
-                    None => write!(f, "{}", self.data[idx])?
-                }
-            }
-            writeln!(f, " |")?;
+    // Compute LU factorization with partial pivoting using DGETRF, see `LU` for details.
+    // Factorization of singular matrix is completed, but cannot be used to solve or invert.
+    pub fn lu(&self) -> LU {
+        unwrap_or_panic(self.try_lu())
+    }
+
+    // Compute LU factorization, returns error if DGETRF failed to execute
+    pub fn try_lu(&self) -> MatrixResult<LU> {
+        let (rows, cols) = self.shape();
+        let mut a = dcopy![self.data()];
+        let mut ipiv = vec![0i32; cmp::min(rows, cols)];
+        let mut info = 0i32;
+
+        unsafe {
+            dgetrf(
+                rows as i32, // m: i32,
+                cols as i32, // n: i32,
+                &mut a, // a: &mut [f64],
+                cmp::max(1, rows) as i32, // lda: i32,
+                &mut ipiv, // ipiv: &mut [i32],
+                &mut info // info: &mut i32
+            );
         }
 
-        writeln!(f, "  . {:>width$} .", "", width = max_length_with_space - 1)?;
-        writeln!(f, "")
+        // when info is positive, factorization is completed, but U is exactly singular
+        ensure!(info >= 0, MatrixError::InvalidArgument(
+            format!("GETRF failed to execute with code {}.", info)));
+        Ok(LU { lu: DoubleMatrix::new(rows, cols, a), ipiv, info })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Solve A * X = B for square matrix A, panics if matrix is singular
+    pub fn solve(&self, b: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_solve(b))
+    }
 
-    fn assert_matrix_eps(a: &DoubleMatrix, b: &DoubleMatrix, epsilon: f64) {
-        assert_eq!(a.shape(), b.shape(), "Shape mismatch: {:?} != {:?}", a, b);
-        let vec1 = a.data();
-        let vec2 = b.data();
-        for i in 0..vec1.len() {
-            assert!((vec1[i] - vec2[i]).abs() <= epsilon,
-                "Element mismatch {} != {}; a: {:?}, b: {:?}", vec1[i], vec2[i], a, b);
-        }
+    // Solve A * X = B for square matrix A, returns error if matrix is singular
+    pub fn try_solve(&self, b: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        self.try_lu()?.try_solve(b)
     }
 
-    fn assert_matrix(a: &DoubleMatrix, b: &DoubleMatrix) {
-        assert_matrix_eps(a, b, 1e-8);
+    // Compute inverse of square matrix, panics if matrix is singular
+    pub fn inverse(&self) -> DoubleMatrix {
+        unwrap_or_panic(self.try_inverse())
     }
 
-    fn assert_eq_f64_eps(a: f64, b: f64, eps: f64) {
-        assert!((a - b).abs() <= eps, "Element mismatch {} != {}", a, b);
+    // Compute inverse of square matrix, returns error if matrix is singular
+    pub fn try_inverse(&self) -> MatrixResult<DoubleMatrix> {
+        self.try_lu()?.try_inverse()
     }
 
-    fn assert_eq_f64(a: f64, b: f64) {
-        assert_eq_f64_eps(a, b, 1e-8);
+    // Compute determinant of square matrix
+    pub fn determinant(&self) -> f64 {
+        unwrap_or_panic(self.try_determinant())
     }
 
-    fn test_matrix_1() -> DoubleMatrix {
-        DoubleMatrix::from_row_slice(3, 4, &[
-            0.25, 0.16, 0.03, 0.23,
-            0.42, 0.33, 0.52, 0.27,
-            0.71, 0.94, 0.37, 0.58
-        ])
+    // Compute determinant of square matrix, returns error if matrix is not square
+    pub fn try_determinant(&self) -> MatrixResult<f64> {
+        self.try_lu()?.try_determinant()
     }
 
-    fn test_matrix_2() -> DoubleMatrix {
-        DoubleMatrix::from_row_slice(4, 4, &[
-            1.0, 1.0, 0.0, 0.0,
-            0.0, 2.0, 1.0, 0.0,
-            0.0, 0.0, 3.0, 1.0,
-            0.0, 0.0, 0.0, 4.0
-        ])
+    // Compute QR factorization without pivoting using DGEQRF and DORGQR
+    pub fn qr(&self) -> QR {
+        unwrap_or_panic(self.try_qr())
     }
 
-    fn test_matrix_3() -> DoubleMatrix {
-        DoubleMatrix::from_row_slice(2, 4, &[
-            1.0, 2.0, 3.0, 4.0,
-            5.0, 6.0, 7.0, 8.0
-        ])
+    // Compute QR factorization without pivoting, returns error if LAPACK routine failed
+    pub fn try_qr(&self) -> MatrixResult<QR> {
+        self.qr_op(false)
     }
 
-    fn test_matrix_4() -> DoubleMatrix {
-        DoubleMatrix::from_row_slice(3, 2, &[
-            1.0, 2.0,
-            2.0, 4.0,
-            3.0, 5.0
-        ])
+    // Compute QR factorization with column pivoting using DGEQP3 and DORGQR, absolute values of
+    // diagonal elements of R are non-increasing, which reveals numerical rank of the matrix
+    pub fn qr_pivoted(&self) -> QR {
+        unwrap_or_panic(self.try_qr_pivoted())
     }
 
-    fn test_index_conversion(rows: usize, cols: usize) {
-        let matrix = DoubleMatrix::new_random(rows, cols);
-        for i in 0..matrix.rows {
-            for j in 0..matrix.cols {
-                assert_eq!(matrix.v2m(matrix.m2v(i, j)), (i, j))
+    // Compute QR factorization with column pivoting, returns error if LAPACK routine failed
+    pub fn try_qr_pivoted(&self) -> MatrixResult<QR> {
+        self.qr_op(true)
+    }
+
+    fn qr_op(&self, pivoting: bool) -> MatrixResult<QR> {
+        let (rows, cols) = self.shape();
+        let k = cmp::min(rows, cols);
+        let mut a = dcopy![self.data()];
+        let mut tau = vec![0f64; k];
+        // all columns are free columns when pivoting
+        let mut jpvt = vec![0i32; cols];
+        let mut info = 0i32;
+
+        // estimate size of lwork
+        let lwork = -1;
+        let mut work = vec![0f64; 1];
+
+        unsafe {
+            if pivoting {
+                dgeqp3(rows as i32, cols as i32, &mut [], cmp::max(1, rows) as i32,
+                    &mut [], &mut [], &mut work, lwork, &mut info);
+            } else {
+                dgeqrf(rows as i32, cols as i32, &mut [], cmp::max(1, rows) as i32,
+                    &mut [], &mut work, lwork, &mut info);
+            }
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let mut work = vec![0f64; lwork];
+
+        unsafe {
+            if pivoting {
+                dgeqp3(rows as i32, cols as i32, &mut a, cmp::max(1, rows) as i32, &mut jpvt,
+                    &mut tau, &mut work, lwork as i32, &mut info);
+            } else {
+                dgeqrf(rows as i32, cols as i32, &mut a, cmp::max(1, rows) as i32, &mut tau,
+                    &mut work, lwork as i32, &mut info);
+            }
+        }
+
+        let routine = if pivoting { "GEQP3" } else { "GEQRF" };
+        ensure!(info == 0, MatrixError::InvalidArgument(
+            format!("{} failed to execute with code {}.", routine, info)));
+
+        // R is stored on and above diagonal of a
+        let mut r = DoubleMatrix::zeros(k, cols);
+        for j in 0..cols {
+            for i in 0..cmp::min(j + 1, k) {
+                r.put(i, j, a[j * rows + i]);
+            }
+        }
+
+        // Q is generated from elementary reflectors below diagonal of first k columns of a
+        a.truncate(rows * k);
+        let lwork = -1;
+        let mut work = vec![0f64; 1];
+
+        unsafe {
+            dorgqr(rows as i32, k as i32, k as i32, &mut [], cmp::max(1, rows) as i32, &tau,
+                &mut work, lwork, &mut info);
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let mut work = vec![0f64; lwork];
+
+        unsafe {
+            dorgqr(rows as i32, k as i32, k as i32, &mut a, cmp::max(1, rows) as i32, &tau,
+                &mut work, lwork as i32, &mut info);
+        }
+
+        ensure!(info == 0, MatrixError::InvalidArgument(
+            format!("ORGQR failed to execute with code {}.", info)));
+
+        // column j of A * P is column jpvt[j] of A (1-based)
+        let p = if pivoting {
+            let mut p = DoubleMatrix::zeros(cols, cols);
+            for j in 0..cols {
+                p.put(jpvt[j] as usize - 1, j, 1f64);
+            }
+            Some(p)
+        } else {
+            None
+        };
+
+        Ok(QR { q: DoubleMatrix::new(rows, k, a), r, p })
+    }
+
+    // Solve linear least squares problem min || B - A * X || using SVD-based DGELSD, which
+    // handles rank-deficient matrices; singular values s(i) <= rcond * s(0) are treated as zero,
+    // when rcond is negative, machine precision is used instead. For underdetermined systems
+    // minimum norm solution is returned.
+    pub fn lstsq(&self, b: &DoubleMatrix, rcond: f64) -> LeastSquares {
+        unwrap_or_panic(self.try_lstsq(b, rcond))
+    }
+
+    // Solve linear least squares problem using DGELSD, returns error if dimensions do not match
+    // or DGELSD did not converge
+    pub fn try_lstsq(&self, b: &DoubleMatrix, rcond: f64) -> MatrixResult<LeastSquares> {
+        let (rows, cols) = self.shape();
+        self.check_lstsq_rows(b)?;
+        let nrhs = b.cols();
+        let ldb = cmp::max(1, cmp::max(rows, cols));
+        let mut a = dcopy![self.data()];
+        let mut x = Self::pad_rows(b, ldb);
+        let mut s = vec![0f64; cmp::min(rows, cols)];
+        let mut rank = 0i32;
+        let mut info = 0i32;
+
+        // estimate size of lwork and liwork
+        let lwork = -1;
+        let mut work = vec![0f64; 1];
+        let mut iwork = vec![0i32; 1];
+
+        unsafe {
+            dgelsd(rows as i32, cols as i32, nrhs as i32, &mut [], cmp::max(1, rows) as i32,
+                &mut [], ldb as i32, &mut [], rcond, &mut rank, &mut work, lwork,
+                &mut iwork, &mut info);
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let mut work = vec![0f64; lwork];
+        let mut iwork = vec![0i32; cmp::max(1, iwork[0] as usize)];
+
+        unsafe {
+            dgelsd(rows as i32, cols as i32, nrhs as i32, &mut a, cmp::max(1, rows) as i32,
+                &mut x, ldb as i32, &mut s, rcond, &mut rank, &mut work, lwork as i32,
+                &mut iwork, &mut info);
+        }
+
+        ensure!(info >= 0, MatrixError::InvalidArgument(
+            format!("GELSD failed to execute with code {}.", info)));
+        ensure!(info == 0,
+            MatrixError::NotConverged(format!("GELSD did not converge, {}.", info), info));
+
+        let x = Self::truncate_rows(x, ldb, cols, nrhs);
+        let residuals = self.residual_norms(&x, b);
+        let s = DoubleMatrix::new(s.len(), 1, s);
+        Ok(LeastSquares { x, rank: rank as usize, residuals, s: Some(s) })
+    }
+
+    // Solve linear least squares problem min || B - A * X || using QR-based DGELS, faster than
+    // `lstsq`, but requires matrix to have full rank; for underdetermined systems minimum norm
+    // solution is returned.
+    pub fn lstsq_qr(&self, b: &DoubleMatrix) -> LeastSquares {
+        unwrap_or_panic(self.try_lstsq_qr(b))
+    }
+
+    // Solve linear least squares problem using DGELS, returns error if dimensions do not match or
+    // matrix does not have full rank
+    pub fn try_lstsq_qr(&self, b: &DoubleMatrix) -> MatrixResult<LeastSquares> {
+        let (rows, cols) = self.shape();
+        self.check_lstsq_rows(b)?;
+        let nrhs = b.cols();
+        let ldb = cmp::max(1, cmp::max(rows, cols));
+        let mut a = dcopy![self.data()];
+        let mut x = Self::pad_rows(b, ldb);
+        let mut info = 0i32;
+
+        // estimate size of lwork
+        let lwork = -1;
+        let mut work = vec![0f64; 1];
+
+        unsafe {
+            dgels(b'N', rows as i32, cols as i32, nrhs as i32, &mut [],
+                cmp::max(1, rows) as i32, &mut [], ldb as i32, &mut work, lwork, &mut info);
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let mut work = vec![0f64; lwork];
+
+        unsafe {
+            dgels(b'N', rows as i32, cols as i32, nrhs as i32, &mut a,
+                cmp::max(1, rows) as i32, &mut x, ldb as i32, &mut work, lwork as i32, &mut info);
+        }
+
+        ensure!(info >= 0, MatrixError::InvalidArgument(
+            format!("GELS failed to execute with code {}.", info)));
+        ensure!(info == 0, MatrixError::Singular(format!("Matrix does not have full rank: \
+            R({}, {}) is exactly zero, use lstsq for rank-deficient matrices.", info - 1,
+            info - 1), info));
+
+        let x = Self::truncate_rows(x, ldb, cols, nrhs);
+        let residuals = self.residual_norms(&x, b);
+        Ok(LeastSquares { x, rank: cmp::min(rows, cols), residuals, s: None })
+    }
+
+    #[inline]
+    fn check_lstsq_rows(&self, b: &DoubleMatrix) -> MatrixResult<()> {
+        ensure!(b.rows() == self.rows(), MatrixError::ShapeMismatch(format!("Dimensions mismatch \
+            for least squares: {} (rows of b) != {} (rows of a).", b.rows(), self.rows())));
+        Ok(())
+    }
+
+    // Copy matrix into column-major buffer with leading dimension ld >= rows, used as right-hand
+    // side for least squares solvers
+    fn pad_rows(b: &DoubleMatrix, ld: usize) -> Vec<f64> {
+        let (rows, cols) = b.shape();
+        let mut buf = vec![0f64; ld * cols];
+        for j in 0..cols {
+            buf[j * ld..j * ld + rows].copy_from_slice(&b.data[j * rows..(j + 1) * rows]);
+        }
+        buf
+    }
+
+    // Extract first rows of each column from buffer with leading dimension ld
+    fn truncate_rows(buf: Vec<f64>, ld: usize, rows: usize, cols: usize) -> DoubleMatrix {
+        let mut data = vec![0f64; rows * cols];
+        for j in 0..cols {
+            data[j * rows..(j + 1) * rows].copy_from_slice(&buf[j * ld..j * ld + rows]);
+        }
+        DoubleMatrix::new(rows, cols, data)
+    }
+
+    // Compute 2-norm of each column of B - A * X
+    fn residual_norms(&self, x: &DoubleMatrix, b: &DoubleMatrix) -> DoubleMatrix {
+        let res = b.sub_matrix(&self.mmul(x));
+        let rows = res.rows() as i32;
+        let norms = res.data.chunks(cmp::max(1, res.rows()))
+            .take(res.cols())
+            .map(|col| unsafe { dnrm2(rows, col, 1i32) })
+            .collect::<Vec<f64>>();
+        DoubleMatrix::new(res.cols(), 1, norms)
+    }
+
+    // Compute Cholesky factorization A = L * L^T of symmetric positive definite matrix using
+    // DPOTRF, returns lower triangular factor L. Only lower triangle of A is referenced.
+    // Panics if matrix is not positive definite, see `try_cholesky` to handle the error.
+    pub fn cholesky(&self) -> DoubleMatrix {
+        unwrap_or_panic(self.try_cholesky())
+    }
+
+    // Compute Cholesky factorization, returns error if matrix is not square or not positive
+    // definite instead of panicking.
+    pub fn try_cholesky(&self) -> MatrixResult<DoubleMatrix> {
+        self.check_square()?;
+        let (rows, cols) = self.shape();
+
+        let mut a = dcopy![self.data()];
+        let mut info = 0i32;
+
+        unsafe {
+            dpotrf(
+                b'L', // uplo: u8,
+                rows as i32, // n: i32,
+                &mut a, // a: &mut [f64],
+                cmp::max(1, rows) as i32, // lda: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        ensure!(info >= 0, MatrixError::InvalidArgument(
+            format!("POTRF failed to execute with code {}.", info)));
+        ensure!(info == 0, MatrixError::Singular(format!("Matrix is not positive definite: \
+            leading minor of order {} is not positive.", info), info));
+
+        // strictly upper triangle is not referenced by DPOTRF and contains original values
+        for j in 1..cols {
+            for i in 0..j {
+                a[j * rows + i] = 0f64;
+            }
+        }
+        Ok(DoubleMatrix::new(rows, cols, a))
+    }
+
+    // Solve A * X = B for symmetric positive definite matrix A using Cholesky factorization and
+    // DPOTRS. Panics if matrix is not positive definite, see `try_cholesky_solve`.
+    pub fn cholesky_solve(&self, b: &DoubleMatrix) -> DoubleMatrix {
+        unwrap_or_panic(self.try_cholesky_solve(b))
+    }
+
+    // Solve A * X = B using Cholesky factorization, returns error if matrix is not positive
+    // definite instead of panicking.
+    pub fn try_cholesky_solve(&self, b: &DoubleMatrix) -> MatrixResult<DoubleMatrix> {
+        let l = self.try_cholesky()?;
+        let n = l.rows();
+        ensure!(b.rows() == n, MatrixError::ShapeMismatch(format!(
+            "Dimensions mismatch for solve: {} (rows of b) != {} (n).", b.rows(), n)));
+
+        let mut x = dcopy![b.data()];
+        let mut info = 0i32;
+
+        unsafe {
+            dpotrs(
+                b'L', // uplo: u8,
+                n as i32, // n: i32,
+                b.cols() as i32, // nrhs: i32,
+                l.data(), // a: &[f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                &mut x, // b: &mut [f64],
+                cmp::max(1, n) as i32, // ldb: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        ensure!(info == 0, MatrixError::InvalidArgument(
+            format!("POTRS failed to execute with code {}.", info)));
+        Ok(DoubleMatrix::new(n, b.cols(), x))
+    }
+
+    // Whether or not matrix is symmetric and positive definite, i.e. Cholesky factorization
+    // exists; symmetry is checked exactly.
+    pub fn is_positive_definite(&self) -> bool {
+        let (rows, cols) = self.shape();
+        if rows != cols {
+            return false;
+        }
+        for j in 0..cols {
+            for i in j + 1..rows {
+                if self.get(i, j) != self.get(j, i) {
+                    return false;
+                }
+            }
+        }
+        self.try_cholesky().is_ok()
+    }
+
+    // == Eigendecomposition ==
+
+    // Compute all eigenvalues and eigenvectors of symmetric matrix using divide-and-conquer
+    // DSYEVD, eigenvalues are returned in ascending order. Only lower triangle is referenced.
+    pub fn eigh(&self) -> Eigen {
+        unwrap_or_panic(self.try_eigh())
+    }
+
+    // Compute eigendecomposition of symmetric matrix, returns error if matrix is not square or
+    // DSYEVD did not converge
+    pub fn try_eigh(&self) -> MatrixResult<Eigen> {
+        self.check_square()?;
+        let n = self.rows();
+        let mut a = dcopy![self.data()];
+        let mut w = vec![0f64; n];
+        let mut info = 0i32;
+
+        // estimate size of lwork and liwork
+        let lwork = -1;
+        let liwork = -1;
+        let mut work = vec![0f64; 1];
+        let mut iwork = vec![0i32; 1];
+
+        unsafe {
+            dsyevd(
+                b'V', // jobz: u8,
+                b'L', // uplo: u8,
+                n as i32, // n: i32,
+                &mut [], // a: &mut [f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                &mut [], // w: &mut [f64],
+                &mut work, // work: &mut [f64],
+                lwork, // lwork: i32,
+                &mut iwork, // iwork: &mut [i32],
+                liwork, // liwork: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let liwork = cmp::max(1, iwork[0] as usize);
+        let mut work = vec![0f64; lwork];
+        let mut iwork = vec![0i32; liwork];
+
+        unsafe {
+            dsyevd(
+                b'V', // jobz: u8,
+                b'L', // uplo: u8,
+                n as i32, // n: i32,
+                &mut a, // a: &mut [f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                &mut w, // w: &mut [f64],
+                &mut work, // work: &mut [f64],
+                lwork as i32, // lwork: i32,
+                &mut iwork, // iwork: &mut [i32],
+                liwork as i32, // liwork: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        // this is strict check; when info is negative, then ith parameter has illegal value
+        ensure!(info == 0,
+            MatrixError::NotConverged(format!("SYEVD did not converge, {}.", info), info));
+        Ok(Eigen { values: DoubleMatrix::new(n, 1, w), vectors: DoubleMatrix::new(n, n, a) })
+    }
+
+    // Compute subset of eigenvalues and eigenvectors of symmetric matrix selected by range using
+    // DSYEVR, eigenvalues are returned in ascending order. Returns however many eigenvalues were
+    // found, which can be fewer than requested (or none) for value range.
+    pub fn eigh_range(&self, range: EigenRange) -> Eigen {
+        unwrap_or_panic(self.try_eigh_range(range))
+    }
+
+    // Compute subset of eigenvalues and eigenvectors of symmetric matrix, returns error for
+    // invalid range or when DSYEVR did not converge
+    pub fn try_eigh_range(&self, range: EigenRange) -> MatrixResult<Eigen> {
+        self.check_square()?;
+        let n = self.rows();
+        // range parameters for DSYEVR, il and iu are 1-based indices, only one pair is referenced
+        let (rng, vl, vu, il, iu, maxm) = match range {
+            EigenRange::Index(start, end) => {
+                ensure!(start < end && end <= n, MatrixError::InvalidArgument(format!(
+                    "Invalid range of eigenvalues: [{}, {}) for {} values.", start, end, n)));
+                ('I', 0f64, 0f64, start + 1, end, end - start)
+            },
+            EigenRange::Value(vl, vu) => {
+                ensure!(vl < vu, MatrixError::InvalidArgument(
+                    format!("Invalid interval of eigenvalues: ({}, {}].", vl, vu)));
+                // Frobenius norm bounds absolute values of eigenvalues, replace infinite bounds
+                let bound = 2f64 * self.norm2() + 1f64;
+                let vl = if vl.is_infinite() { -bound } else { vl };
+                let vu = if vu.is_infinite() { bound } else { vu };
+                if vl >= vu {
+                    // interval lies outside of the spectrum, no eigenvalues found
+                    return Ok(Eigen {
+                        values: DoubleMatrix::new(0, 1, vec![]),
+                        vectors: DoubleMatrix::new(n, 0, vec![])
+                    });
+                }
+                ('V', vl, vu, 1, n, n)
+            }
+        };
+
+        let mut a = dcopy![self.data()];
+        let mut m = 0i32; // number of eigenvalues found
+        let mut w = vec![0f64; n];
+        let mut z = vec![0f64; n * maxm];
+        let mut isuppz = vec![0i32; 2 * cmp::max(1, maxm)];
+        let mut info = 0i32;
+
+        // estimate size of lwork and liwork
+        let lwork = -1;
+        let liwork = -1;
+        let mut work = vec![0f64; 1];
+        let mut iwork = vec![0i32; 1];
+
+        unsafe {
+            dsyevr(
+                b'V', // jobz: u8,
+                rng as u8, // range: u8,
+                b'L', // uplo: u8,
+                n as i32, // n: i32,
+                &mut [], // a: &mut [f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                vl, // vl: f64,
+                vu, // vu: f64,
+                il as i32, // il: i32,
+                iu as i32, // iu: i32,
+                0f64, // abstol: f64,
+                &mut m, // m: &mut i32,
+                &mut [], // w: &mut [f64],
+                &mut [], // z: &mut [f64],
+                cmp::max(1, n) as i32, // ldz: i32,
+                &mut [], // isuppz: &mut [i32],
+                &mut work, // work: &mut [f64],
+                lwork, // lwork: i32,
+                &mut iwork, // iwork: &mut [i32],
+                liwork, // liwork: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let liwork = cmp::max(1, iwork[0] as usize);
+        let mut work = vec![0f64; lwork];
+        let mut iwork = vec![0i32; liwork];
+
+        unsafe {
+            dsyevr(
+                b'V', // jobz: u8,
+                rng as u8, // range: u8,
+                b'L', // uplo: u8,
+                n as i32, // n: i32,
+                &mut a, // a: &mut [f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                vl, // vl: f64,
+                vu, // vu: f64,
+                il as i32, // il: i32,
+                iu as i32, // iu: i32,
+                0f64, // abstol: f64,
+                &mut m, // m: &mut i32,
+                &mut w, // w: &mut [f64],
+                &mut z, // z: &mut [f64],
+                cmp::max(1, n) as i32, // ldz: i32,
+                &mut isuppz, // isuppz: &mut [i32],
+                &mut work, // work: &mut [f64],
+                lwork as i32, // lwork: i32,
+                &mut iwork, // iwork: &mut [i32],
+                liwork as i32, // liwork: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        // this is strict check; when info is negative, then ith parameter has illegal value
+        ensure!(info == 0,
+            MatrixError::NotConverged(format!("SYEVR did not converge, {}.", info), info));
+        let m = cmp::min(cmp::max(m, 0) as usize, maxm);
+        w.truncate(m);
+        // z is stored column-major with ldz = n, first m columns hold eigenvectors
+        z.truncate(n * m);
+        Ok(Eigen { values: DoubleMatrix::new(m, 1, w), vectors: DoubleMatrix::new(n, m, z) })
+    }
+
+    // Compute k largest eigenvalues and eigenvectors of symmetric matrix, eigenvalues are
+    // returned in descending order, e.g. principal components of covariance matrix.
+    pub fn eigh_top(&self, k: usize) -> Eigen {
+        unwrap_or_panic(self.try_eigh_top(k))
+    }
+
+    // Compute k largest eigenvalues and eigenvectors of symmetric matrix, returns error for
+    // invalid k or when DSYEVR did not converge
+    pub fn try_eigh_top(&self, k: usize) -> MatrixResult<Eigen> {
+        let n = self.rows();
+        ensure!(k >= 1 && k <= n, MatrixError::InvalidArgument(
+            format!("Invalid number of eigenvalues: {}.", k)));
+        let eigen = self.try_eigh_range(EigenRange::Index(n - k, n))?;
+        // reverse order of eigenvalues and eigenvectors
+        let mut values = eigen.values.data;
+        values.reverse();
+        let mut vectors = DoubleMatrix::zeros(n, k);
+        for j in 0..k {
+            let src = (k - 1 - j) * n;
+            vectors.data[j * n..(j + 1) * n].copy_from_slice(&eigen.vectors.data[src..src + n]);
+        }
+        Ok(Eigen { values: DoubleMatrix::new(k, 1, values), vectors })
+    }
+
+    // Compute eigenvalues and right eigenvectors of general square matrix using DGEEV
+    pub fn eig(&self) -> GeneralEigen {
+        self.eig_with(false, true)
+    }
+
+    // Compute eigenvalues and right eigenvectors, returns error instead of panicking
+    pub fn try_eig(&self) -> MatrixResult<GeneralEigen> {
+        self.try_eig_with(false, true)
+    }
+
+    // Compute eigenvalues and, optionally, left and right eigenvectors of general square matrix
+    // using DGEEV, see `GeneralEigen` for details.
+    pub fn eig_with(&self, left: bool, right: bool) -> GeneralEigen {
+        unwrap_or_panic(self.try_eig_with(left, right))
+    }
+
+    // Compute eigendecomposition of general square matrix, returns error if matrix is not square
+    // or DGEEV did not converge
+    pub fn try_eig_with(&self, left: bool, right: bool) -> MatrixResult<GeneralEigen> {
+        self.check_square()?;
+        let n = self.rows();
+        let mut a = dcopy![self.data()];
+        let mut wr = vec![0f64; n];
+        let mut wi = vec![0f64; n];
+        // left eigenvectors, not referenced when jobvl = 'N'
+        let jobvl = if left { 'V' } else { 'N' };
+        let ldvl = if left { n } else { 1 };
+        let mut vl = vec![0f64; if left { n * n } else { 0 }];
+        // right eigenvectors, not referenced when jobvr = 'N'
+        let jobvr = if right { 'V' } else { 'N' };
+        let ldvr = if right { n } else { 1 };
+        let mut vr = vec![0f64; if right { n * n } else { 0 }];
+        let mut info = 0i32;
+
+        // estimate size of lwork
+        let lwork = -1;
+        let mut work = vec![0f64; 1];
+
+        unsafe {
+            dgeev(
+                jobvl as u8, // jobvl: u8,
+                jobvr as u8, // jobvr: u8,
+                n as i32, // n: i32,
+                &mut [], // a: &mut [f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                &mut [], // wr: &mut [f64],
+                &mut [], // wi: &mut [f64],
+                &mut [], // vl: &mut [f64],
+                cmp::max(1, ldvl) as i32, // ldvl: i32,
+                &mut [], // vr: &mut [f64],
+                cmp::max(1, ldvr) as i32, // ldvr: i32,
+                &mut work, // work: &mut [f64],
+                lwork, // lwork: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        check_workspace!(info);
+
+        let lwork = cmp::max(1, work[0] as usize);
+        let mut work = vec![0f64; lwork];
+
+        unsafe {
+            dgeev(
+                jobvl as u8, // jobvl: u8,
+                jobvr as u8, // jobvr: u8,
+                n as i32, // n: i32,
+                &mut a, // a: &mut [f64],
+                cmp::max(1, n) as i32, // lda: i32,
+                &mut wr, // wr: &mut [f64],
+                &mut wi, // wi: &mut [f64],
+                &mut vl, // vl: &mut [f64],
+                cmp::max(1, ldvl) as i32, // ldvl: i32,
+                &mut vr, // vr: &mut [f64],
+                cmp::max(1, ldvr) as i32, // ldvr: i32,
+                &mut work, // work: &mut [f64],
+                lwork as i32, // lwork: i32,
+                &mut info // info: &mut i32
+            );
+        }
+
+        // when info is positive, QR algorithm failed to compute all eigenvalues
+        ensure!(info >= 0, MatrixError::InvalidArgument(
+            format!("GEEV failed to execute with code {}.", info)));
+        ensure!(info == 0, MatrixError::NotConverged(
+            format!("GEEV did not converge, eigenvalues 0..{} were not computed.", info), info));
+
+        let left = if left { Some(Self::unpack_eigenvectors(n, &wi, &vl)) } else { None };
+        let right = if right { Some(Self::unpack_eigenvectors(n, &wi, &vr)) } else { None };
+        let values = ComplexMatrix {
+            re: DoubleMatrix::new(n, 1, wr),
+            im: DoubleMatrix::new(n, 1, wi)
+        };
+        Ok(GeneralEigen { values, left, right })
+    }
+
+    // Unpack eigenvectors computed by DGEEV: for complex conjugate pair of eigenvalues j and j + 1
+    // columns j and j + 1 store real and imaginary parts of eigenvector j, and eigenvector j + 1
+    // is its complex conjugate.
+    fn unpack_eigenvectors(n: usize, wi: &[f64], v: &[f64]) -> ComplexMatrix {
+        let mut re = DoubleMatrix::zeros(n, n);
+        let mut im = DoubleMatrix::zeros(n, n);
+        let mut j = 0;
+        while j < n {
+            if wi[j] == 0f64 || j + 1 == n {
+                re.data[j * n..(j + 1) * n].copy_from_slice(&v[j * n..(j + 1) * n]);
+                j += 1;
+            } else {
+                for i in 0..n {
+                    let (x, y) = (v[j * n + i], v[(j + 1) * n + i]);
+                    re.put(i, j, x);
+                    im.put(i, j, y);
+                    re.put(i, j + 1, x);
+                    im.put(i, j + 1, -y);
+                }
+                j += 2;
             }
         }
+        ComplexMatrix { re, im }
+    }
+}
+
+impl LinearOperator for DoubleMatrix {
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn matvec(&self, x: &[f64], y: &mut [f64]) {
+        assert_eq!(x.len(), self.cols,
+            "Invalid length of input vector: {} != {}.", x.len(), self.cols);
+        assert_eq!(y.len(), self.rows,
+            "Invalid length of output vector: {} != {}.", y.len(), self.rows);
+        let (m, n) = (self.rows as i32, self.cols as i32);
+        unsafe { dgemv(b'N', m, n, 1f64, self.data(), cmp::max(1, m), x, 1, 0f64, y, 1); }
+    }
+
+    fn rmatvec(&self, x: &[f64], y: &mut [f64]) {
+        assert_eq!(x.len(), self.rows,
+            "Invalid length of input vector: {} != {}.", x.len(), self.rows);
+        assert_eq!(y.len(), self.cols,
+            "Invalid length of output vector: {} != {}.", y.len(), self.cols);
+        let (m, n) = (self.rows as i32, self.cols as i32);
+        unsafe { dgemv(b'T', m, n, 1f64, self.data(), cmp::max(1, m), x, 1, 0f64, y, 1); }
+    }
+}
+
+// Experimental svd for top k singular values of linear operator, see `DoubleMatrix::lansvd` for
+// details. Matrix-vector products are dispatched to the operator through `operator_matmul`.
+// Panic in the operator is reported as error after DLANSVD_IRL returns.
+pub fn lansvd_operator<T: LinearOperator>(op: &T, k: usize) -> SVD {
+    lansvd_operator_with(op, k, &LanczosOptions::new())
+}
+
+// Experimental svd for top k singular values of linear operator, returns error for invalid
+// options, failed operator or when not all singular triplets converged
+pub fn try_lansvd_operator<T: LinearOperator>(op: &T, k: usize) -> MatrixResult<SVD> {
+    try_lansvd_operator_with(op, k, &LanczosOptions::new())
+}
+
+// Experimental svd for top k singular values of linear operator with provided options.
+pub fn lansvd_operator_with<T: LinearOperator>(op: &T, k: usize, options: &LanczosOptions) -> SVD {
+    unwrap_or_panic(try_lansvd_operator_with(op, k, options))
+}
+
+// Experimental svd for top k singular values of linear operator with provided options, returns
+// error instead of panicking
+pub fn try_lansvd_operator_with<T: LinearOperator>(
+    op: &T,
+    k: usize,
+    options: &LanczosOptions
+) -> MatrixResult<SVD> {
+    try_lansvd_operator_result(op, k, options)?.try_into_svd()
+}
+
+// Experimental svd for top k singular values of linear operator with convergence diagnostics.
+pub fn lansvd_operator_result<T: LinearOperator>(
+    op: &T,
+    k: usize,
+    options: &LanczosOptions
+) -> LanczosSvd {
+    unwrap_or_panic(try_lansvd_operator_result(op, k, options))
+}
+
+// Experimental svd for top k singular values of linear operator with convergence diagnostics,
+// returns error for invalid options or failed operator
+pub fn try_lansvd_operator_result<T: LinearOperator>(
+    op: &T,
+    k: usize,
+    options: &LanczosOptions
+) -> MatrixResult<LanczosSvd> {
+    let (rows, cols) = op.shape();
+    let mut context = OperatorContext::new(op);
+    let res = {
+        let dparm = &mut context as *mut OperatorContext as *const f64;
+        unsafe { try_lansvd_op(rows, cols, k, options, Some(operator_matmul), dparm, ptr::null()) }
+    };
+    // operator failure takes precedence over any error in DLANSVD_IRL
+    if let Some(err) = context.take_error() {
+        return Err(err);
+    }
+    res
+}
+
+// Compute top k singular triplets of (rows x cols) matrix A with DLANSVD using Krylov subspace of
+// dimension at most kmax, where A is defined by APROD function and its `dparm` and `iparm`
+// pointers, that are passed to APROD unchanged.
+pub unsafe fn lansvd_pro_op(
+    rows: usize,
+    cols: usize,
+    k: usize,
+    kmax: usize,
+    aprod: PROPACK_EXTERN,
+    dparm: *const f64,
+    iparm: *const i32
+) -> SVD {
+    unwrap_or_panic(try_lansvd_pro_op(rows, cols, k, kmax, aprod, dparm, iparm))
+}
+
+// Compute top k singular triplets with DLANSVD, returns error for invalid arguments or when
+// DLANSVD did not converge, see `lansvd_pro_op` for details.
+pub unsafe fn try_lansvd_pro_op(
+    rows: usize,
+    cols: usize,
+    k: usize,
+    kmax: usize,
+    aprod: PROPACK_EXTERN,
+    dparm: *const f64,
+    iparm: *const i32
+) -> MatrixResult<SVD> {
+    let lanmax = cmp::min(rows, cols);
+    DoubleMatrix::check_num_values(k, lanmax)?;
+    ensure!(kmax >= k, MatrixError::InvalidArgument(
+        format!("Invalid dimension of Krylov subspace: {} (k = {}).", kmax, k)));
+    // Krylov subspace dimension cannot exceed min(rows, cols) + 1
+    let kmax = cmp::min(kmax, lanmax + 1);
+
+    // desired relative accuracy of computed singular values
+    let tolin = 1e-8;
+
+    // left singular vectors
+    let (urows, ucols) = (rows, kmax + 1);
+    let mut u = vec![0f64; urows * ucols];
+
+    let mut sigma = vec![0f64; kmax];
+    let mut bnd = vec![0f64; kmax];
+
+    // right singular vectors
+    let (vrows, vcols) = (kmax, cols);
+    let mut v = vec![0f64; vrows * vcols];
+
+    // size of work, block size for computing singular vectors is 32
+    let lwork = rows + cols + 9 * kmax + 5 * kmax * kmax + 4 +
+        cmp::max(3 * kmax * kmax + 4 * kmax + 4, 32 * cmp::max(rows, cols));
+    let mut work = vec![0f64; lwork];
+
+    // size of iwork
+    let liwork = 8 * kmax;
+    let mut iwork = vec![0i32; liwork];
+
+    let doption = vec![
+        // level of orthogonality to maintain among Lanczos vectors
+        f64::EPSILON.sqrt(),
+        // during reorthogonalization, all vectors with with components larger than this value
+        // along the latest Lanczos vector c will be purged
+        f64::EPSILON.powf(3.0 / 4.0),
+        // estimate of || A ||
+        0f64
+    ];
+
+    let ioption = vec![
+        // reorthogonalization is done using iterated modified Gram-Schmidt
+        0i32,
+        // extended local orthogonality is enforced among u_{k}, u_{k+1} and v_{k} and v_{k+1}
+        // respectively
+        1i32
+    ];
+
+    // status info
+    let mut info = 0i32;
+
+    // number of computed singular triplets on output
+    let mut neig = k as i32;
+
+    propack_call(|| dlansvd(
+        b'Y',
+        b'Y',
+        rows as i32,
+        cols as i32,
+        &mut neig,
+        kmax as i32,
+        aprod,
+        &mut u,
+        cmp::max(1, urows) as i32,
+        &mut sigma,
+        &mut bnd,
+        &mut v,
+        cmp::max(1, vcols) as i32,
+        tolin,
+        &mut work,
+        lwork as i32,
+        &mut iwork,
+        liwork as i32,
+        &doption,
+        &ioption,
+        &mut info,
+        dparm,
+        iparm
+    ))?;
+
+    ensure!(info != -1, MatrixError::NotConverged(
+        "DLANSVD, K singular triplets did not converge within KMAX iterations.".to_string(), info));
+    ensure!(info == 0, MatrixError::NotConverged(
+        format!("DLANSVD, an invariant subspace of dimension J was found, {}.", info), info));
+    // DLANSVD also stops with info = 0 when invariant subspace of dimension J >= K is found, but
+    // only neig singular values have converged and singular vectors are not computed
+    ensure!(neig as usize >= k, MatrixError::NotConverged(
+        format!("DLANSVD, only {} of {} singular triplets converged.", neig, k), neig));
+
+    // truncate u to (rows, k)
+    u.truncate(rows * k);
+    let u = DoubleMatrix::new(rows, k, u);
+
+    // truncate sigma to k
+    sigma.truncate(k);
+    let s = DoubleMatrix::new(k, 1, sigma);
+
+    // truncate v to (cols, k), v is not transposed
+    v.truncate(cols * k);
+    let v = DoubleMatrix::new(cols, k, v);
+
+    Ok(SVD { u: Some(u), s, v: Some(v) })
+}
+
+// Compute top k singular triplets of (rows x cols) matrix A with DLANSVD_IRL, where A is defined
+// by APROD function and its `dparm` and `iparm` pointers, that are passed to APROD unchanged.
+// Result contains only converged singular triplets, see `LanczosSvd` for details.
+pub unsafe fn lansvd_op(
+    rows: usize,
+    cols: usize,
+    k: usize,
+    options: &LanczosOptions,
+    aprod: PROPACK_EXTERN,
+    dparm: *const f64,
+    iparm: *const i32
+) -> LanczosSvd {
+    unwrap_or_panic(try_lansvd_op(rows, cols, k, options, aprod, dparm, iparm))
+}
+
+// Compute top k singular triplets with DLANSVD_IRL, returns error for invalid options, see
+// `lansvd_op` for details.
+pub unsafe fn try_lansvd_op(
+    rows: usize,
+    cols: usize,
+    k: usize,
+    options: &LanczosOptions,
+    aprod: PROPACK_EXTERN,
+    dparm: *const f64,
+    iparm: *const i32
+) -> MatrixResult<LanczosSvd> {
+    let lanmax = cmp::min(rows, cols);
+    DoubleMatrix::check_num_values(k, lanmax)?;
+
+    // number of desired singular triplets.
+    let neig = cmp::min(k, lanmax);
+    // dimension of Krylov subspace
+    let kmax = options.dim.unwrap_or(cmp::min(7 * neig, lanmax + 1));
+    ensure!(kmax > neig && kmax <= lanmax + 1, MatrixError::InvalidArgument(
+        format!("Invalid dimension of Krylov subspace: {} (k = {}).", kmax, neig)));
+    let dim = kmax;
+    // number of shift per restart
+    let p = options.shifts.unwrap_or(dim - neig);
+    ensure!(p >= 1 && p <= dim - neig, MatrixError::InvalidArgument(format!(
+        "Invalid number of shifts per restart: {} (dim = {}, k = {}).", p, dim, neig)));
+    // maximum number of restarts
+    let maxiter = options.max_restarts as i32;
+    // desired relative accuracy of computed singular values
+    let tolin = options.tolerance;
+
+    // left singular vectors
+    let (urows, ucols) = (rows, kmax + 1);
+    let mut u = vec![0f64; urows * ucols];
+
+    let mut sigma = vec![0f64; kmax];
+    let mut bnd = vec![0f64; kmax];
+
+    // right singular vectors
+    let (vrows, vcols) = (kmax, cols);
+    let mut v = vec![0f64; vrows * vcols];
+
+    // size of work
+    let lwork = rows + cols + 14 * kmax + 8 * kmax * kmax + 32 * rows + 9;
+    // work buffer
+    let mut work = vec![0f64; lwork];
+
+    // size of iwork
+    let liwork = 8 * kmax;
+    let mut iwork = vec![0i32; liwork];
+
+    // doption
+    let doption = vec![
+        // level of orthogonality to maintain among Lanczos vectors
+        f64::EPSILON.sqrt(),
+        // during reorthogonalization, all vectors with with components larger than this value
+        // along the latest Lanczos vector c will be purged
+        f64::EPSILON.powf(3.0 / 4.0),
+        // estimate of || A ||
+        0f64,
+        // smallest relgap between any shift the smallest requested Ritz value
+        0.002
+    ];
+
+    let ioption = vec![
+        // reorthogonalization is done using iterated classical (1) or modified (0) Gram-Schmidt
+        if options.classical_gs { 1i32 } else { 0i32 },
+        // extended local orthogonality is enforced among u_{k}, u_{k+1} and v_{k} and v_{k+1}
+        // respectively
+        if options.extended_local_orth { 1i32 } else { 0i32 }
+    ];
+
+    // status info
+    let mut info = 0i32;
+    // number of desired singular triplets on input, number of converged triplets on output
+    let mut nconv = neig as i32;
+    // APROD is wrapped to count matrix-vector products of this call
+    let mut context = CountingContext::new(aprod, dparm, iparm);
+    let cparm = &mut context as *mut CountingContext as *const f64;
+
+    let (_, restarts) = propack_call(|| dlansvd_irl(
+        options.which.as_char() as u8,
+        if options.job.left() { b'Y' } else { b'N' },
+        if options.job.right() { b'Y' } else { b'N' },
+        rows as i32,
+        cols as i32,
+        dim as i32,
+        p as i32,
+        &mut nconv,
+        maxiter,
+        Some(counting_matmul),
+        &mut u,
+        cmp::max(1, urows) as i32,
+        &mut sigma,
+        &mut bnd,
+        &mut v,
+        cmp::max(1, vcols) as i32,
+        tolin,
+        &mut work,
+        lwork as i32,
+        &mut iwork,
+        liwork as i32,
+        &doption,
+        &ioption,
+        &mut info,
+        cparm,
+        ptr::null()
+    ))?;
+
+    let nconv = cmp::max(0, cmp::min(nconv, neig as i32)) as usize;
+    let status = match info {
+        // DLANSVD_IRL never reports non-convergence, it returns info = 0 with fewer converged
+        // triplets when it runs out of restarts
+        0 if nconv < neig => LanczosStatus::NotConverged,
+        0 => LanczosStatus::Converged,
+        j => LanczosStatus::InvariantSubspace(j as usize)
+    };
+
+    // singular vectors are only computed when requested and all triplets converged or subspace
+    // was found, otherwise arrays contain Lanczos vectors
+    let computed = status != LanczosStatus::NotConverged;
+    let u = if computed && options.job.left() {
+        // truncate u to (rows, nconv)
+        u.truncate(rows * nconv);
+        Some(DoubleMatrix::new(rows, nconv, u))
+    } else {
+        None
+    };
+    let v = if computed && options.job.right() {
+        // truncate v to (nconv, cols)
+        // note that v comes already transposed (not as v^T), so we just swap rows and cols
+        v.truncate(cols * nconv);
+        Some(DoubleMatrix::new(cols, nconv, v))
+    } else {
+        None
+    };
+
+    // truncate sigma and error bounds to nconv
+    sigma.truncate(nconv);
+    let s = DoubleMatrix::new(nconv, 1, sigma);
+    bnd.truncate(nconv);
+    let bnd = DoubleMatrix::new(nconv, 1, bnd);
+
+    Ok(LanczosSvd {
+        svd: SVD { u, s, v },
+        bnd,
+        requested: neig,
+        restarts,
+        matvecs: context.matvecs(),
+        status
+    })
+}
+
+extern "C" {
+    // from lapack-sys, redefine with mutable A, since DGELSD overwrites A, but lapack binding
+    // takes it as immutable slice
+    fn dgelsd_(
+        m: *const i32,
+        n: *const i32,
+        nrhs: *const i32,
+        a: *mut f64,
+        lda: *const i32,
+        b: *mut f64,
+        ldb: *const i32,
+        s: *mut f64,
+        rcond: *const f64,
+        rank: *mut i32,
+        work: *mut f64,
+        lwork: *const i32,
+        iwork: *mut i32,
+        info: *mut i32
+    );
+}
+
+#[inline]
+unsafe fn dgelsd(
+    m: i32,
+    n: i32,
+    nrhs: i32,
+    a: &mut [f64],
+    lda: i32,
+    b: &mut [f64],
+    ldb: i32,
+    s: &mut [f64],
+    rcond: f64,
+    rank: &mut i32,
+    work: &mut [f64],
+    lwork: i32,
+    iwork: &mut [i32],
+    info: &mut i32
+) {
+    dgelsd_(&m, &n, &nrhs, a.as_mut_ptr(), &lda, b.as_mut_ptr(), &ldb, s.as_mut_ptr(), &rcond,
+        rank, work.as_mut_ptr(), &lwork, iwork.as_mut_ptr(), info)
+}
+
+impl Clone for DoubleMatrix {
+    fn clone(&self) -> Self {
+        DoubleMatrix::new(self.rows, self.cols, dcopy![self.data()])
+    }
+}
+
+impl Display for DoubleMatrix {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        fn val_width(val: f64, f: &mut Formatter) -> usize {
+            match f.precision() {
+                Some(precision) => format!("{:.1$}", val, precision).chars().count(),
+                None => format!("{}", val).chars().count()
+            }
+        }
+
+        let (rows, cols) = self.shape();
+
+        if rows == 0 || cols == 0 {
+            return write!(f, "[ ]");
+        }
+
+        let mut max_length_with_space = 0;
+        let mut col_lengths = vec![0; cols];
+
+        for j in 0..cols {
+            for i in 0..rows {
+                let idx = self.m2v(i, j);
+                col_lengths[j] = cmp::max(col_lengths[j], val_width(self.data[idx], f));
+            }
+            max_length_with_space += col_lengths[j] + 1;
+        }
+
+        writeln!(f, "")?;
+        writeln!(f, "  . {:>width$} .", "", width = max_length_with_space - 1)?;
+
+        for i in 0..rows {
+            write!(f, "  |")?;
+            for j in 0..cols {
+                let idx = self.m2v(i, j);
+                let pad = col_lengths[j] - val_width(self.data[idx], f);
+                write!(f, " {:>thepad$}", "", thepad = pad)?;
+                match f.precision() {
+                    Some(precision) => write!(f, "{:.1$}", self.data[idx], precision)?,
+                    None => write!(f, "{}", self.data[idx])?
+                }
+            }
+            writeln!(f, " |")?;
+        }
+
+        writeln!(f, "  . {:>width$} .", "", width = max_length_with_space - 1)?;
+        writeln!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lanczos::Which;
+
+    fn assert_matrix_eps(a: &DoubleMatrix, b: &DoubleMatrix, epsilon: f64) {
+        assert_eq!(a.shape(), b.shape(), "Shape mismatch: {:?} != {:?}", a, b);
+        let vec1 = a.data();
+        let vec2 = b.data();
+        for i in 0..vec1.len() {
+            assert!((vec1[i] - vec2[i]).abs() <= epsilon,
+                "Element mismatch {} != {}; a: {:?}, b: {:?}", vec1[i], vec2[i], a, b);
+        }
+    }
+
+    fn assert_matrix(a: &DoubleMatrix, b: &DoubleMatrix) {
+        assert_matrix_eps(a, b, 1e-8);
+    }
+
+    fn assert_eq_f64_eps(a: f64, b: f64, eps: f64) {
+        assert!((a - b).abs() <= eps, "Element mismatch {} != {}", a, b);
+    }
+
+    fn assert_eq_f64(a: f64, b: f64) {
+        assert_eq_f64_eps(a, b, 1e-8);
+    }
+
+    // Check that A * v = s * u for each singular triplet
+    fn assert_svd_triplets(a: &DoubleMatrix, svd: &SVD, epsilon: f64) {
+        let (u, v) = (svd.u.as_ref().unwrap(), svd.v.as_ref().unwrap());
+        let av = a.mmul(v);
+        for j in 0..svd.s.rows() {
+            for i in 0..a.rows() {
+                assert_eq_f64_eps(av.get(i, j), svd.s.get(j, 0) * u.get(i, j), epsilon);
+            }
+        }
+    }
+
+    fn test_matrix_1() -> DoubleMatrix {
+        DoubleMatrix::from_row_slice(3, 4, &[
+            0.25, 0.16, 0.03, 0.23,
+            0.42, 0.33, 0.52, 0.27,
+            0.71, 0.94, 0.37, 0.58
+        ])
+    }
+
+    fn test_matrix_2() -> DoubleMatrix {
+        DoubleMatrix::from_row_slice(4, 4, &[
+            1.0, 1.0, 0.0, 0.0,
+            0.0, 2.0, 1.0, 0.0,
+            0.0, 0.0, 3.0, 1.0,
+            0.0, 0.0, 0.0, 4.0
+        ])
+    }
+
+    fn test_matrix_3() -> DoubleMatrix {
+        DoubleMatrix::from_row_slice(2, 4, &[
+            1.0, 2.0, 3.0, 4.0,
+            5.0, 6.0, 7.0, 8.0
+        ])
+    }
+
+    fn test_matrix_4() -> DoubleMatrix {
+        DoubleMatrix::from_row_slice(3, 2, &[
+            1.0, 2.0,
+            2.0, 4.0,
+            3.0, 5.0
+        ])
+    }
+
+    fn test_index_conversion(rows: usize, cols: usize) {
+        let matrix = DoubleMatrix::new_random(rows, cols);
+        for i in 0..matrix.rows {
+            for j in 0..matrix.cols {
+                assert_eq!(matrix.v2m(matrix.m2v(i, j)), (i, j))
+            }
+        }
+    }
+
+    #[test]
+    fn test_index_conversions() {
+        test_index_conversion(123, 57);
+        test_index_conversion(57, 123);
+        test_index_conversion(1, 123);
+        test_index_conversion(123, 1);
+        test_index_conversion(1, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimensions mismatch: 3 * 2 != 100")]
+    fn test_new_dim_mismatch() {
+        DoubleMatrix::new(3, 2, vec![1f64; 100]);
+    }
+
+    #[test]
+    fn test_new() {
+        let matrix = DoubleMatrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(matrix.rows(), 2);
+        assert_eq!(matrix.cols(), 3);
+        assert_eq!(matrix.data(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(matrix.shape(), (2, 3));
+    }
+
+    #[test]
+    fn test_get_put_get() {
+        let mut matrix = DoubleMatrix::ones(34, 56);
+        for i in 0..matrix.rows() {
+            for j in 0..matrix.cols() {
+                assert_eq!(matrix.get(i, j), 1.0);
+            }
+        }
+
+        for i in 0..matrix.rows() {
+            for j in 0..matrix.cols() {
+                matrix.put(i, j, (i + j) as f64);
+            }
+        }
+
+        for i in 0..matrix.rows() {
+            for j in 0..matrix.cols() {
+                assert_eq!(matrix.get(i, j), (i + j) as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_identity() {
+        let matrix = DoubleMatrix::identity(3, 3);
+        assert_eq!(matrix.data(), &[
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0
+        ]);
+
+        let matrix = DoubleMatrix::identity(3, 4);
+        assert_eq!(matrix.data(), &[
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+            0.0, 0.0, 0.0
+        ]);
+
+        let matrix = DoubleMatrix::identity(4, 3);
+        assert_eq!(matrix.data(), &[
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0
+        ]);
+    }
+
+    #[test]
+    fn test_elementwise_add_scalar() {
+        let matrix = DoubleMatrix::zeros(4, 5);
+        let res = matrix.add_scalar(1f64);
+        assert_matrix(&res, &DoubleMatrix::ones(4, 5));
+    }
+
+    #[test]
+    fn test_elementwise_add_matrix() {
+        let matrix = DoubleMatrix::zeros(4, 5);
+        let res = matrix.add_matrix(&DoubleMatrix::ones(4, 5));
+        assert_matrix(&res, &DoubleMatrix::ones(4, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
+    fn test_elementwise_add_matrix_panic() {
+        DoubleMatrix::new_random(3, 4).add_matrix(&DoubleMatrix::new_random(2, 3));
+    }
+
+    #[test]
+    fn test_elementwise_sub_scalar() {
+        let matrix = DoubleMatrix::ones(4, 5);
+        let res = matrix.sub_scalar(1f64);
+        assert_matrix(&res, &DoubleMatrix::zeros(4, 5));
+    }
+
+    #[test]
+    fn test_elementwise_sub_matrix() {
+        let matrix = DoubleMatrix::ones(4, 5);
+        let res = matrix.sub_matrix(&DoubleMatrix::ones(4, 5));
+        assert_matrix(&res, &DoubleMatrix::zeros(4, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
+    fn test_elementwise_sub_matrix_panic() {
+        DoubleMatrix::new_random(3, 4).sub_matrix(&DoubleMatrix::new_random(2, 3));
+    }
+
+    #[test]
+    fn test_elementwise_mul_scalar() {
+        let matrix = DoubleMatrix::ones(4, 5);
+        let res = matrix.mul_scalar(3f64);
+        let exp = matrix.add_scalar(1f64).add_scalar(1f64);
+        assert_matrix(&res, &exp);
+    }
+
+    #[test]
+    fn test_elementwise_mul_matrix() {
+        let matrix = DoubleMatrix::ones(4, 5);
+        let res = matrix.mul_matrix(&DoubleMatrix::identity(4, 5));
+        assert_matrix(&res, &DoubleMatrix::identity(4, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
+    fn test_elementwise_mul_matrix_panic() {
+        DoubleMatrix::new_random(3, 4).mul_matrix(&DoubleMatrix::new_random(2, 3));
+    }
+
+    #[test]
+    fn test_elementwise_div_scalar() {
+        let matrix = DoubleMatrix::ones(4, 5);
+        let res = matrix.div_scalar(0.5);
+        assert_matrix(&res, &matrix.mul_scalar(2f64));
+    }
+
+    #[test]
+    fn test_elementwise_div_matrix() {
+        let matrix = DoubleMatrix::new_random(4, 5);
+        let res = matrix.div_matrix(&matrix);
+        assert_matrix(&res, &DoubleMatrix::ones(4, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
+    fn test_elementwise_div_matrix_panic() {
+        DoubleMatrix::new_random(3, 4).div_matrix(&DoubleMatrix::new_random(2, 3));
+    }
+
+    #[test]
+    fn test_column_sums() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(1, 4, vec![1.38, 1.43, 0.92, 1.08]);
+        assert_matrix(&matrix.column_sums(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
+        assert_matrix(&matrix.column_sums(), &matrix);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+        let exp = DoubleMatrix::new(1, 1, vec![6.0]);
+        assert_matrix(&matrix.column_sums(), &exp);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
+        assert_matrix(&matrix.column_sums(), &matrix);
+    }
+
+    #[test]
+    fn test_column_mins() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(1, 4, vec![0.25, 0.16, 0.03, 0.23]);
+        assert_matrix(&matrix.column_mins(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
+        assert_matrix(&matrix.column_mins(), &matrix);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![2.0, 1.0, 3.0]);
+        let exp = DoubleMatrix::new(1, 1, vec![1.0]);
+        assert_matrix(&matrix.column_mins(), &exp);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
+        assert_matrix(&matrix.column_mins(), &matrix);
+    }
+
+    #[test]
+    fn test_column_maxs() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(1, 4, vec![0.71, 0.94, 0.52, 0.58]);
+        assert_matrix(&matrix.column_maxs(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
+        assert_matrix(&matrix.column_mins(), &matrix);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![2.0, 1.0, 3.0]);
+        let exp = DoubleMatrix::new(1, 1, vec![3.0]);
+        assert_matrix(&matrix.column_maxs(), &exp);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
+        assert_matrix(&matrix.column_maxs(), &matrix);
+    }
+
+    #[test]
+    fn test_column_means() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(1, 4, vec![0.46, 0.47666667, 0.30666667, 0.36]);
+        assert_matrix(&matrix.column_means(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
+        assert_matrix(&matrix.column_means(), &matrix);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![21.0, 12.0, 36.0]);
+        let exp = DoubleMatrix::new(1, 1, vec![23.0]);
+        assert_matrix(&matrix.column_means(), &exp);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
+        assert_matrix(&matrix.column_means(), &matrix);
+    }
+
+    #[test]
+    fn test_row_sums() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(3, 1, vec![0.67, 1.54, 2.6]);
+        assert_matrix(&matrix.row_sums(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 4, vec![0.25, 0.16, 0.03, 0.23]);
+        let exp = DoubleMatrix::new(1, 1, vec![0.67]);
+        assert_matrix(&matrix.row_sums(), &exp);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
+        assert_matrix(&matrix.row_sums(), &matrix);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
+        assert_matrix(&matrix.row_sums(), &matrix);
+    }
+
+    #[test]
+    fn test_row_mins() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(3, 1, vec![0.03, 0.27, 0.37]);
+        assert_matrix(&matrix.row_mins(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 3, vec![0.1, 0.2, 0.3]);
+        let exp = DoubleMatrix::new(1, 1, vec![0.1]);
+        assert_matrix(&matrix.row_mins(), &exp);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
+        assert_matrix(&matrix.row_mins(), &matrix);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
+        assert_matrix(&matrix.row_mins(), &matrix);
+    }
+
+    #[test]
+    fn test_row_maxs() {
+        // full matrix
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(3, 1, vec![0.25, 0.52, 0.94]);
+        assert_matrix(&matrix.row_maxs(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 3, vec![0.1, 0.2, 0.3]);
+        let exp = DoubleMatrix::new(1, 1, vec![0.3]);
+        assert_matrix(&matrix.row_maxs(), &exp);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
+        assert_matrix(&matrix.row_maxs(), &matrix);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
+        assert_matrix(&matrix.row_maxs(), &matrix);
+    }
+
+    #[test]
+    fn test_row_means() {
+        let matrix = test_matrix_1();
+        let exp = DoubleMatrix::new(3, 1, vec![0.1675, 0.385, 0.65]);
+        assert_matrix(&matrix.row_means(), &exp);
+
+        // row vector
+        let matrix = DoubleMatrix::new(1, 4, vec![0.71, 0.94, 0.37, 0.58]);
+        let exp = DoubleMatrix::new(1, 1, vec![0.65]);
+        assert_matrix(&matrix.row_means(), &exp);
+
+        // column vector
+        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
+        assert_matrix(&matrix.row_means(), &matrix);
+
+        // single element
+        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
+        assert_matrix(&matrix.row_means(), &matrix);
+    }
+
+    #[test]
+    fn test_mmul() {
+        let a = test_matrix_1();
+        assert_matrix(&a.mmul(&DoubleMatrix::identity(4, 4)), &a);
+
+        let b = test_matrix_4();
+        let exp = DoubleMatrix::from_row_slice(2, 2, &[
+            14.0, 25.0,
+            25.0, 45.0
+        ]);
+        assert_matrix(&b.transpose().mmul(&b), &exp);
+    }
+
+    #[test]
+    fn test_gemm_transpose() {
+        let a = test_matrix_1();
+        let b = test_matrix_4();
+        let c = a.transpose().mmul(&b);
+        assert_matrix(&a.mmul_transpose(&b, true, false), &c);
+        assert_matrix(&b.mmul_transpose(&a, true, false), &c.transpose());
+        assert_matrix(&a.mmul_transpose(&a, false, true), &a.mmul(&a.transpose()));
+        assert_matrix(&b.mmul_transpose(&a.transpose(), true, true), &b.transpose().mmul(&a));
+    }
+
+    #[test]
+    fn test_gemm_alpha_beta() {
+        let a = test_matrix_1();
+        let b = test_matrix_4();
+        let mut c = DoubleMatrix::ones(4, 2);
+        DoubleMatrix::gemm(true, false, 2.0, &a, &b, 0.5, &mut c);
+        let exp = a.transpose().mmul(&b).mul_scalar(2.0).add_scalar(0.5);
+        assert_matrix(&c, &exp);
+
+        // accumulate c += a^T * b
+        DoubleMatrix::gemm(true, false, 1.0, &a, &b, 1.0, &mut c);
+        let exp = exp.add_matrix(&a.transpose().mmul(&b));
+        assert_matrix(&c, &exp);
+    }
+
+    #[test]
+    #[should_panic(expected = "Input dimensions mismatch for multiplication.")]
+    fn test_gemm_input_mismatch() {
+        let mut c = DoubleMatrix::zeros(3, 2);
+        DoubleMatrix::gemm(false, false, 1.0, &test_matrix_1(), &test_matrix_4(), 0.0, &mut c);
+    }
+
+    #[test]
+    #[should_panic(expected = "Output dimensions mismatch for multiplication.")]
+    fn test_gemm_output_mismatch() {
+        let mut c = DoubleMatrix::zeros(3, 2);
+        DoubleMatrix::gemm(true, false, 1.0, &test_matrix_1(), &test_matrix_4(), 0.0, &mut c);
+    }
+
+    #[test]
+    fn test_gram() {
+        let a = test_matrix_4();
+        let exp = DoubleMatrix::from_row_slice(2, 2, &[
+            14.0, 25.0,
+            25.0, 45.0
+        ]);
+        assert_matrix(&a.gram(), &exp);
+
+        let a = test_matrix_1();
+        assert_matrix(&a.gram(), &a.transpose().mmul(&a));
+        assert_matrix(&a.gram_rows(), &a.mmul(&a.transpose()));
+    }
+
+    #[test]
+    fn test_syrk_alpha_beta() {
+        let a = test_matrix_1();
+        let mut c = DoubleMatrix::identity(3, 3);
+        DoubleMatrix::syrk(false, 2.0, &a, 3.0, &mut c);
+        let exp = a.mmul(&a.transpose()).mul_scalar(2.0)
+            .add_matrix(&DoubleMatrix::identity(3, 3).mul_scalar(3.0));
+        assert_matrix(&c, &exp);
+    }
+
+    #[test]
+    #[should_panic(expected = "Output dimensions mismatch for multiplication.")]
+    fn test_syrk_output_mismatch() {
+        let mut c = DoubleMatrix::zeros(3, 3);
+        DoubleMatrix::syrk(true, 1.0, &test_matrix_1(), 0.0, &mut c);
+    }
+
+    #[test]
+    fn test_mv() {
+        let a = test_matrix_3();
+        let x = DoubleMatrix::new(4, 1, vec![1.0, 0.0, -1.0, 2.0]);
+        let y = a.mv(&x);
+        assert_matrix(&y, &DoubleMatrix::new(2, 1, vec![6.0, 14.0]));
+        assert_matrix(&y, &a.mmul(&x));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid shape for column vector: (2, 1).")]
+    fn test_mv_invalid_shape() {
+        test_matrix_3().mv(&DoubleMatrix::ones(2, 1));
+    }
+
+    #[test]
+    fn test_tmv() {
+        let a = test_matrix_3();
+        let x = DoubleMatrix::new(2, 1, vec![1.0, -1.0]);
+        let y = a.tmv(&x);
+        assert_matrix(&y, &DoubleMatrix::new(4, 1, vec![-4.0, -4.0, -4.0, -4.0]));
+        assert_matrix(&y, &a.transpose().mmul(&x));
+    }
+
+    #[test]
+    fn test_outer() {
+        let x = DoubleMatrix::new(2, 1, vec![1.0, 2.0]);
+        let y = DoubleMatrix::new(3, 1, vec![1.0, 0.0, -1.0]);
+        let exp = DoubleMatrix::from_row_slice(2, 3, &[
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -2.0
+        ]);
+        assert_matrix(&DoubleMatrix::outer(&x, &y), &exp);
+        assert_matrix(&DoubleMatrix::outer(&x, &y), &x.mmul(&y.transpose()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid shape for column vector: (1, 2).")]
+    fn test_outer_invalid_shape() {
+        DoubleMatrix::outer(&DoubleMatrix::ones(2, 1), &DoubleMatrix::ones(1, 2));
+    }
+
+    #[test]
+    fn test_dot() {
+        let x = DoubleMatrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+        let y = DoubleMatrix::new(3, 1, vec![4.0, -5.0, 6.0]);
+        assert_eq_f64(x.dot(&y), 12.0);
+        let a = test_matrix_1();
+        assert_eq_f64(a.dot(&a), a.norm2().powi(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shape mismatch: (3, 1) != (1, 3).")]
+    fn test_dot_shape_mismatch() {
+        DoubleMatrix::ones(3, 1).dot(&DoubleMatrix::ones(1, 3));
+    }
+
+    #[test]
+    fn test_axpy() {
+        let mut y = test_matrix_1();
+        let x = DoubleMatrix::ones(3, 4);
+        y.axpy(2.0, &x);
+        assert_matrix(&y, &test_matrix_1().add_scalar(2.0));
+        y.axpy(-1.0, &test_matrix_1());
+        assert_matrix(&y, &DoubleMatrix::ones(3, 4).mul_scalar(2.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid shape for column vector: (4, 5).")]
+    fn test_put_get_column_invalid_shape() {
+        let mut matrix = test_matrix_1();
+        let (rows, cols) = matrix.shape();
+        matrix.put_column(1, &DoubleMatrix::ones(rows + 1, cols + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid column index 4 (4 columns).")]
+    fn test_put_get_column_invalid_index_1() {
+        let mut matrix = test_matrix_1();
+        let (rows, cols) = matrix.shape();
+        matrix.put_column(cols, &DoubleMatrix::ones(rows, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid column index 4 (4 columns).")]
+    fn test_put_get_column_invalid_index_2() {
+        let matrix = test_matrix_1();
+        matrix.get_column(matrix.cols());
+    }
+
+    #[test]
+    fn test_put_get_column() {
+        let mut matrix = test_matrix_1();
+        let (rows, cols) = matrix.shape();
+        let vector = DoubleMatrix::ones(rows, 1);
+        matrix.put_column(1, &vector);
+        assert_matrix(&matrix.get_column(1), &vector);
+        for col in 0..cols {
+            matrix.put_column(col, &vector);
+        }
+        assert_matrix(&matrix, &DoubleMatrix::ones(rows, cols));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid shape for row vector: (4, 5).")]
+    fn test_put_get_row_invalid_shape() {
+        let mut matrix = test_matrix_1();
+        let (rows, cols) = matrix.shape();
+        matrix.put_row(1, &DoubleMatrix::ones(rows + 1, cols + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid row index 3 (3 rows).")]
+    fn test_put_get_row_invalid_index_1() {
+        let mut matrix = test_matrix_1();
+        let (rows, cols) = matrix.shape();
+        matrix.put_row(rows, &DoubleMatrix::ones(1, cols));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid row index 3 (3 rows).")]
+    fn test_put_get_row_invalid_index_2() {
+        let matrix = test_matrix_1();
+        matrix.get_row(matrix.rows());
+    }
+
+    #[test]
+    fn test_put_get_row() {
+        let mut matrix = test_matrix_1();
+        let (rows, cols) = matrix.shape();
+        let vector = DoubleMatrix::ones(1, cols);
+        matrix.put_row(1, &vector);
+        assert_matrix(&matrix.get_row(1), &vector);
+        for row in 0..rows {
+            matrix.put_row(row, &vector);
+        }
+        assert_matrix(&matrix, &DoubleMatrix::ones(rows, cols));
+    }
+
+    #[test]
+    fn test_matrix_min() {
+        assert_eq!(test_matrix_1().min(), 0.03);
+        assert_eq!(test_matrix_2().min(), 0.0);
+        assert_eq!(test_matrix_3().min(), 1.0);
+        assert_eq!(test_matrix_4().min(), 1.0);
+    }
+
+    #[test]
+    fn test_matrix_max() {
+        assert_eq!(test_matrix_1().max(), 0.94);
+        assert_eq!(test_matrix_2().max(), 4.0);
+        assert_eq!(test_matrix_3().max(), 8.0);
+        assert_eq!(test_matrix_4().max(), 5.0);
+    }
+
+    #[test]
+    fn test_matrix_sum() {
+        assert_eq!(test_matrix_1().sum(), 4.81);
+        assert_eq!(test_matrix_2().sum(), 13.0);
+        assert_eq!(test_matrix_3().sum(), 36.0);
+        assert_eq!(test_matrix_4().sum(), 17.0);
+    }
+
+    #[test]
+    fn test_norm1() {
+        assert_eq_f64(test_matrix_1().norm1(), 4.81);
+        assert_eq_f64(test_matrix_2().norm1(), 13.0);
+        assert_eq_f64(test_matrix_3().norm1(), 36.0);
+        assert_eq_f64(test_matrix_4().norm1(), 17.0);
+    }
+
+    #[test]
+    fn test_norm2() {
+        assert_eq_f64(test_matrix_1().norm2(), 1.622189877911954);
+        assert_eq_f64(test_matrix_2().norm2(), 5.744562646538029);
+        assert_eq_f64(test_matrix_3().norm2(), 14.2828568570857);
+        assert_eq_f64(test_matrix_4().norm2(), 7.681145747868608);
+    }
+
+    #[test]
+    fn test_transpose() {
+        assert_matrix(&test_matrix_1().transpose().transpose(), &test_matrix_1());
+        assert_matrix(&test_matrix_2().transpose().transpose(), &test_matrix_2());
+        assert_matrix(&test_matrix_3().transpose().transpose(), &test_matrix_3());
+        assert_matrix(&test_matrix_4().transpose().transpose(), &test_matrix_4());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to transpose a non-square matrix in-place.")]
+    fn test_transpose_mut_panic() {
+        let mut matrix = test_matrix_1();
+        matrix.transpose_mut();
+    }
+
+    #[test]
+    fn test_transpose_mut() {
+        let matrix = DoubleMatrix::new_random(3, 3);
+        let mut clone = matrix.clone();
+        clone.transpose_mut();
+        assert_matrix(&clone, &matrix.transpose());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to get the diagonal of a non-square matrix.")]
+    fn test_diag_panic() {
+        test_matrix_1().diag();
+    }
+
+    #[test]
+    fn test_diag() {
+        let matrix = DoubleMatrix::identity(4, 4);
+        assert_matrix(&matrix.diag(), &DoubleMatrix::new(4, 1, vec![1.0, 1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_abs() {
+        assert_matrix(&test_matrix_1().abs(), &test_matrix_1());
+
+        let matrix = DoubleMatrix::new(3, 2, vec![
+            -1.0, 2.0, -3.0,
+            4.0, -5.0, -6.0
+        ]);
+        let exp = DoubleMatrix::new(3, 2, vec![
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0
+        ]);
+        assert_matrix(&matrix.abs(), &exp);
+    }
+
+    #[test]
+    fn test_full_svd_test_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.full_svd();
+
+        let u_exp = DoubleMatrix::from_row_slice(4, 4, &[
+            0.013543, -0.135435, 0.542638, 0.828866,
+            0.109341, -0.518419, 0.667345, -0.523390,
+            0.470163, -0.714229, -0.481962, 0.191143,
+            0.875676, 0.450306, 0.167053, -0.050094
+        ]);
+        let s_exp = DoubleMatrix::from_row_slice(4, 1, &[
+            4.260007, 3.107349, 2.111785, 0.858542
+        ]);
+        let v_exp = DoubleMatrix::from_row_slice(4, 4, &[
+            0.003179, -0.043585, 0.256957, 0.965434,
+            0.054513, -0.377258, 0.888977, -0.253819,
+            0.356767, -0.856391, -0.368665, 0.058285,
+            0.932596, 0.349815, 0.088195, -0.010752
+        ]);
+
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+    }
+
+    #[test]
+    fn test_full_svd_test_matrix_3() {
+        let a = test_matrix_3();
+        let svd = a.full_svd();
+
+        let u_exp = DoubleMatrix::from_row_slice(2, 2, &[
+            -0.376168, -0.926551,
+            -0.926551, 0.376168
+        ]);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            14.227407, 1.257330
+        ]);
+        let v_exp = DoubleMatrix::from_row_slice(4, 4, &[
+            -0.352062, 0.758981, -0.400087, -0.374072,
+            -0.443626, 0.321242, 0.254633, 0.796971,
+            -0.535190, -0.116498, 0.690996, -0.471724,
+            -0.626754, -0.554238, -0.545542, 0.048826
+        ]);
+
+        assert_matrix(&a, &test_matrix_3());
+        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
     }
 
     #[test]
-    fn test_index_conversions() {
-        test_index_conversion(123, 57);
-        test_index_conversion(57, 123);
-        test_index_conversion(1, 123);
-        test_index_conversion(123, 1);
-        test_index_conversion(1, 1);
+    fn test_full_svd_test_matrix_4() {
+        let a = test_matrix_4();
+        let svd = a.full_svd();
+
+        let u_exp = DoubleMatrix::from_row_slice(3, 3, &[
+            -0.291036, -0.339556, -0.894427,
+            -0.582071, -0.679112, 0.447214,
+            -0.759270, 0.650776, -0.000000
+        ]);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            7.675619, 0.291321
+        ]);
+        let v_exp = DoubleMatrix::from_row_slice(2, 2, &[
+            -0.486344, 0.873768,
+            -0.873768, -0.486344
+        ]);
+
+        assert_matrix(&a, &test_matrix_4());
+        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
     }
 
     #[test]
-    #[should_panic(expected = "Dimensions mismatch: 3 * 2 != 100")]
-    fn test_new_dim_mismatch() {
-        DoubleMatrix::new(3, 2, vec![1f64; 100]);
+    fn test_svd_wide_matrix_3() {
+        let a = test_matrix_3();
+        let svd = a.svd(1);
+        let s_exp = DoubleMatrix::from_row_slice(1, 1, &[14.227407]);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.as_ref().unwrap().shape(), (2, 1));
+        assert_eq!(svd.v.as_ref().unwrap().shape(), (4, 1));
+        assert_svd_triplets(&a, &svd, 1e-6);
+
+        let svd = a.svd_job(2, SvdJob::Left);
+        assert_eq!(svd.u.unwrap().shape(), (2, 2));
+        assert!(svd.v.is_none());
     }
 
     #[test]
-    fn test_new() {
-        let matrix = DoubleMatrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
-        assert_eq!(matrix.rows(), 2);
-        assert_eq!(matrix.cols(), 3);
-        assert_eq!(matrix.data(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
-        assert_eq!(matrix.shape(), (2, 3));
+    fn test_lansvd_wide_matrix_1() {
+        let a = test_matrix_1();
+        let s = a.singular_values();
+        let s_exp = DoubleMatrix::new(1, 1, vec![s.get(0, 0)]);
+        let svd = a.lansvd(1);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.as_ref().unwrap().shape(), (3, 1));
+        assert_eq!(svd.v.as_ref().unwrap().shape(), (4, 1));
+        assert_svd_triplets(&a, &svd, 1e-6);
+
+        let svd = a.lansvd_with(1, &LanczosOptions::new().job(SvdJob::Right));
+        assert!(svd.u.is_none());
+        assert_eq!(svd.v.unwrap().shape(), (4, 1));
     }
 
     #[test]
-    fn test_get_put_get() {
-        let mut matrix = DoubleMatrix::ones(34, 56);
-        for i in 0..matrix.rows() {
-            for j in 0..matrix.cols() {
-                assert_eq!(matrix.get(i, j), 1.0);
-            }
-        }
+    fn test_svd_reconstruct() {
+        let a = test_matrix_1();
+        let svd = a.full_svd();
+        assert_eq!(svd.num_values(), 3);
+        assert_matrix(&svd.reconstruct(), &a);
+        assert_eq_f64(svd.reconstruction_error(&a), 0.0);
 
-        for i in 0..matrix.rows() {
-            for j in 0..matrix.cols() {
-                matrix.put(i, j, (i + j) as f64);
-            }
-        }
+        let svd = test_matrix_2().svd(2);
+        assert_eq!(svd.reconstruct().shape(), (4, 4));
+    }
 
-        for i in 0..matrix.rows() {
-            for j in 0..matrix.cols() {
-                assert_eq!(matrix.get(i, j), (i + j) as f64);
-            }
-        }
+    #[test]
+    fn test_svd_truncate() {
+        let a = test_matrix_2();
+        let svd = a.full_svd().truncate(2);
+        assert_eq!(svd.num_values(), 2);
+        assert_eq!(svd.u.as_ref().unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.as_ref().unwrap().shape(), (4, 2));
+        // error of best rank-2 approximation is norm of discarded singular values
+        let err = (2.111785f64.powi(2) + 0.858542f64.powi(2)).sqrt();
+        assert_eq_f64_eps(svd.reconstruction_error(&a), err, 1e-6);
+        assert_matrix(&svd.reconstruct(), &a.svd(2).reconstruct());
     }
 
     #[test]
-    fn test_identity() {
-        let matrix = DoubleMatrix::identity(3, 3);
-        assert_eq!(matrix.data(), &[
-            1.0, 0.0, 0.0,
-            0.0, 1.0, 0.0,
-            0.0, 0.0, 1.0
+    #[should_panic(expected = "Invalid rank for truncation: 5 (4 values).")]
+    fn test_svd_truncate_invalid() {
+        test_matrix_2().full_svd().truncate(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Both left and right singular vectors are required.")]
+    fn test_svd_reconstruct_no_vectors() {
+        test_matrix_2().svd_job(2, SvdJob::Left).reconstruct();
+    }
+
+    #[test]
+    fn test_svd_explained_variance_ratio() {
+        let a = test_matrix_2();
+        let ratio = a.full_svd().explained_variance_ratio(&a);
+        assert_eq_f64(ratio.sum(), 1.0);
+        let ratio = a.svd(1).explained_variance_ratio(&a);
+        assert_eq_f64_eps(ratio.get(0, 0), 4.260007f64.powi(2) / a.norm2().powi(2), 1e-6);
+    }
+
+    #[test]
+    fn test_svd_canonicalize_signs() {
+        let a = test_matrix_2();
+        let mut svd1 = a.full_svd().truncate(2);
+        let mut svd2 = a.svd(2);
+        let mut svd3 = a.lansvd(2);
+        svd1.canonicalize_signs();
+        svd2.canonicalize_signs();
+        svd3.canonicalize_signs();
+        assert_matrix_eps(svd1.u.as_ref().unwrap(), svd2.u.as_ref().unwrap(), 1e-6);
+        assert_matrix_eps(svd1.v.as_ref().unwrap(), svd2.v.as_ref().unwrap(), 1e-6);
+        assert_matrix_eps(svd1.u.as_ref().unwrap(), svd3.u.as_ref().unwrap(), 1e-6);
+        assert_matrix_eps(svd1.v.as_ref().unwrap(), svd3.v.as_ref().unwrap(), 1e-6);
+        assert_matrix(&svd1.reconstruct(), &svd2.reconstruct());
+        // largest component of each column of U is positive
+        let u = svd1.u.unwrap();
+        assert!(u.get(3, 0) > 0.0);
+        assert!(u.get(2, 1) > 0.0);
+    }
+
+    #[test]
+    fn test_svd_job_transpose() {
+        assert_eq!(SvdJob::Values.transpose(), SvdJob::Values);
+        assert_eq!(SvdJob::Left.transpose(), SvdJob::Right);
+        assert_eq!(SvdJob::Right.transpose(), SvdJob::Left);
+        assert_eq!(SvdJob::Both.transpose(), SvdJob::Both);
+    }
+
+    #[test]
+    fn test_singular_values_matrix_2() {
+        let a = test_matrix_2();
+        let s = a.singular_values();
+        let s_exp = DoubleMatrix::from_row_slice(4, 1, &[
+            4.260007, 3.107349, 2.111785, 0.858542
         ]);
+        assert_matrix_eps(&s, &s_exp, 1e-6);
+    }
 
-        let matrix = DoubleMatrix::identity(3, 4);
-        assert_eq!(matrix.data(), &[
-            1.0, 0.0, 0.0,
-            0.0, 1.0, 0.0,
-            0.0, 0.0, 1.0,
-            0.0, 0.0, 0.0
+    #[test]
+    fn test_singular_values_matrix_3() {
+        let a = test_matrix_3();
+        let s = a.singular_values();
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            14.227407, 1.257330
         ]);
+        assert_matrix_eps(&s, &s_exp, 1e-6);
+    }
 
-        let matrix = DoubleMatrix::identity(4, 3);
-        assert_eq!(matrix.data(), &[
-            1.0, 0.0, 0.0, 0.0,
-            0.0, 1.0, 0.0, 0.0,
-            0.0, 0.0, 1.0, 0.0
+    #[test]
+    fn test_svd_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.svd(2);
+
+        let u_exp = DoubleMatrix::from_row_slice(4, 2, &[
+            -0.013543, -0.135435,
+            -0.109341, -0.518419,
+            -0.470163, -0.714229,
+            -0.875676, 0.450306
+        ]);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+
+        let v_exp = DoubleMatrix::from_row_slice(4, 2, &[
+            -0.003179, -0.043585,
+            -0.054513, -0.377258,
+            -0.356767, -0.856391,
+            -0.932596, 0.349815
         ]);
+
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
     }
 
     #[test]
-    fn test_elementwise_add_scalar() {
-        let matrix = DoubleMatrix::zeros(4, 5);
-        let res = matrix.add_scalar(1f64);
-        assert_matrix(&res, &DoubleMatrix::ones(4, 5));
+    fn test_svd_job_matrix_2() {
+        let a = test_matrix_2();
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+
+        let svd = a.svd_job(2, SvdJob::Values);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert!(svd.u.is_none());
+        assert!(svd.v.is_none());
+
+        let svd = a.svd_job(2, SvdJob::Left);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert!(svd.v.is_none());
+
+        let svd = a.svd_job(2, SvdJob::Right);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert!(svd.u.is_none());
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
     }
 
     #[test]
-    fn test_elementwise_add_matrix() {
-        let matrix = DoubleMatrix::zeros(4, 5);
-        let res = matrix.add_matrix(&DoubleMatrix::ones(4, 5));
-        assert_matrix(&res, &DoubleMatrix::ones(4, 5));
+    fn test_lansvd_job_matrix_2() {
+        let a = test_matrix_2();
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+
+        let svd = a.lansvd_with(2, &LanczosOptions::new().job(SvdJob::Values));
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert!(svd.u.is_none());
+        assert!(svd.v.is_none());
+
+        let svd = a.lansvd_with(2, &LanczosOptions::new().job(SvdJob::Right));
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert!(svd.u.is_none());
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
+    }
+
+    #[test]
+    fn test_svd_job_from_char() {
+        assert_eq!(SvdJob::from_char('N'), SvdJob::Values);
+        assert_eq!(SvdJob::from_char('U'), SvdJob::Left);
+        assert_eq!(SvdJob::from_char('V'), SvdJob::Right);
+        assert_eq!(SvdJob::from_char('a'), SvdJob::Both);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid SVD job 'X', expected 'N', 'U', 'V' or 'A'.")]
+    fn test_svd_job_from_char_invalid() {
+        SvdJob::from_char('X');
+    }
+
+    #[test]
+    fn test_svd_index_range_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.svd_range(SvdRange::Index(1, 3), SvdJob::Both);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            3.107349, 2.111785
+        ]);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
+    }
+
+    #[test]
+    fn test_svd_value_range_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.svd_range(SvdRange::Value(2.0, ::std::f64::INFINITY), SvdJob::Both);
+        let s_exp = DoubleMatrix::from_row_slice(3, 1, &[
+            4.260007, 3.107349, 2.111785
+        ]);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 3));
+        assert_eq!(svd.v.unwrap().shape(), (4, 3));
+
+        let svd = a.svd_range(SvdRange::Value(0.5, 1.0), SvdJob::Values);
+        let s_exp = DoubleMatrix::from_row_slice(1, 1, &[0.858542]);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert!(svd.u.is_none());
+        assert!(svd.v.is_none());
+    }
+
+    #[test]
+    fn test_svd_value_range_empty() {
+        let a = test_matrix_2();
+        let svd = a.svd_range(SvdRange::Value(5.0, 10.0), SvdJob::Both);
+        assert_eq!(svd.s.shape(), (0, 1));
+        assert_eq!(svd.u.unwrap().shape(), (4, 0));
+        assert_eq!(svd.v.unwrap().shape(), (4, 0));
+
+        // lower bound is above the bound that replaces infinite upper bound
+        let svd = a.svd_range(SvdRange::Value(100.0, ::std::f64::INFINITY), SvdJob::Left);
+        assert_eq!(svd.s.shape(), (0, 1));
+        assert_eq!(svd.u.unwrap().shape(), (4, 0));
+        assert!(svd.v.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range of singular values: [2, 5) for 4 values.")]
+    fn test_svd_index_range_invalid() {
+        let a = test_matrix_2();
+        a.svd_range(SvdRange::Index(2, 5), SvdJob::Values);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid interval of singular values: (3, 1].")]
+    fn test_svd_value_range_invalid() {
+        let a = test_matrix_2();
+        a.svd_range(SvdRange::Value(3.0, 1.0), SvdJob::Values);
+    }
+
+    #[test]
+    fn test_lansvd_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.lansvd(2);
+
+        let u_exp = DoubleMatrix::from_row_slice(4, 2, &[
+            -0.013543, 0.135435,
+            -0.109341, 0.518419,
+            -0.470163, 0.714229,
+            -0.875676, -0.450306
+        ]);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+
+        let v_exp = DoubleMatrix::from_row_slice(4, 2, &[
+            -0.003179, 0.043585,
+            -0.054513, 0.377258,
+            -0.356767, 0.856391,
+            -0.932596, -0.349815
+        ]);
+
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+    }
+
+    #[test]
+    fn test_lansvd_pro_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.lansvd_pro(2, 4);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid dimension of Krylov subspace: 1 (k = 2).")]
+    fn test_lansvd_pro_invalid_kmax() {
+        test_matrix_2().lansvd_pro(2, 1);
+    }
+
+    #[test]
+    fn test_lansvd_pro_rank_deficient() {
+        // rank 2 matrix, Krylov subspace becomes invariant before k singular triplets converge
+        let a = DoubleMatrix::from_row_slice(4, 4, &[
+            1.0, 2.0, 3.0, 4.0,
+            2.0, 4.0, 6.0, 8.0,
+            1.0, 0.0, 1.0, 0.0,
+            3.0, 4.0, 7.0, 8.0
+        ]);
+        match a.try_lansvd_pro(3, 4) {
+            Err(MatrixError::NotConverged(_, info)) => assert!(info < 3),
+            res => panic!("Unexpected result {:?}", res.map(|svd| svd.s))
+        }
+    }
+
+    #[test]
+    fn test_rsvd_matrix_2() {
+        let a = test_matrix_2();
+        let svd = a.rsvd(2);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
+    }
+
+    #[test]
+    fn test_rsvd_low_rank() {
+        // matrix of rank 5, range is recovered exactly with oversampling
+        let a = DoubleMatrix::new_random(60, 5).mmul(&DoubleMatrix::new_random(5, 40));
+        let svd = a.rsvd_with(5, 5, 1);
+        let s_exp = DoubleMatrix::new(5, 1, a.singular_values().data()[0..5].to_vec());
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
+
+        assert_svd_triplets(&a, &svd, 1e-6);
     }
 
     #[test]
-    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
-    fn test_elementwise_add_matrix_panic() {
-        DoubleMatrix::new_random(3, 4).add_matrix(&DoubleMatrix::new_random(2, 3));
+    #[should_panic(expected = "Invalid number of singular values: 5.")]
+    fn test_rsvd_invalid_k() {
+        test_matrix_2().rsvd(5);
     }
 
     #[test]
-    fn test_elementwise_sub_scalar() {
-        let matrix = DoubleMatrix::ones(4, 5);
-        let res = matrix.sub_scalar(1f64);
-        assert_matrix(&res, &DoubleMatrix::zeros(4, 5));
+    fn test_rank() {
+        assert_eq!(test_matrix_2().rank(-1.0), 4);
+        assert_eq!(test_matrix_4().rank(-1.0), 2);
+        assert_eq!(DoubleMatrix::ones(3, 4).rank(-1.0), 1);
+        assert_eq!(DoubleMatrix::zeros(3, 3).rank(-1.0), 0);
+        // smallest singular value of test matrix 2 is 0.858542
+        assert_eq!(test_matrix_2().rank(1.0), 3);
     }
 
     #[test]
-    fn test_elementwise_sub_matrix() {
-        let matrix = DoubleMatrix::ones(4, 5);
-        let res = matrix.sub_matrix(&DoubleMatrix::ones(4, 5));
-        assert_matrix(&res, &DoubleMatrix::zeros(4, 5));
+    fn test_cond() {
+        assert_eq_f64(test_symmetric_matrix().cond(), 3.0 + 2.0 * 2f64.sqrt());
+        assert_eq_f64(DoubleMatrix::identity(3, 3).cond(), 1.0);
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            1.0, 0.0,
+            0.0, 0.0
+        ]);
+        assert_eq!(a.cond(), ::std::f64::INFINITY);
     }
 
     #[test]
-    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
-    fn test_elementwise_sub_matrix_panic() {
-        DoubleMatrix::new_random(3, 4).sub_matrix(&DoubleMatrix::new_random(2, 3));
+    fn test_pinv() {
+        let a = test_matrix_2();
+        assert_matrix(&a.pinv(-1.0), &a.inverse());
+
+        // pseudo-inverse of M-by-N matrix of ones is N-by-M matrix of 1 / (M * N)
+        let a = DoubleMatrix::ones(2, 3);
+        assert_matrix(&a.pinv(-1.0), &DoubleMatrix::ones(3, 2).div_scalar(6.0));
+
+        let a = test_matrix_4();
+        let p = a.pinv(1e-10);
+        assert_eq!(p.shape(), (2, 3));
+        assert_matrix(&a.mmul(&p).mmul(&a), &a);
+        assert_matrix(&p.mmul(&a).mmul(&p), &p);
     }
 
     #[test]
-    fn test_elementwise_mul_scalar() {
-        let matrix = DoubleMatrix::ones(4, 5);
-        let res = matrix.mul_scalar(3f64);
-        let exp = matrix.add_scalar(1f64).add_scalar(1f64);
-        assert_matrix(&res, &exp);
+    fn test_null_space() {
+        let a = DoubleMatrix::ones(2, 3);
+        let n = a.null_space(-1.0);
+        assert_eq!(n.shape(), (3, 2));
+        assert_matrix(&a.mmul(&n), &DoubleMatrix::zeros(2, 2));
+        assert_matrix(&n.transpose().mmul(&n), &DoubleMatrix::identity(2, 2));
+
+        // tall matrix uses thin SVD, which has all right singular vectors
+        let a = DoubleMatrix::ones(3, 2);
+        let n = a.null_space(-1.0);
+        assert_eq!(n.shape(), (2, 1));
+        assert_matrix(&a.mmul(&n), &DoubleMatrix::zeros(3, 1));
+
+        assert_eq!(test_matrix_2().null_space(-1.0).shape(), (4, 0));
     }
 
     #[test]
-    fn test_elementwise_mul_matrix() {
-        let matrix = DoubleMatrix::ones(4, 5);
-        let res = matrix.mul_matrix(&DoubleMatrix::identity(4, 5));
-        assert_matrix(&res, &DoubleMatrix::identity(4, 5));
+    fn test_range_space() {
+        let a = DoubleMatrix::ones(3, 2);
+        let r = a.range_space(-1.0);
+        assert_eq!(r.shape(), (3, 1));
+        let r_exp = DoubleMatrix::ones(3, 1).div_scalar(3f64.sqrt());
+        assert_matrix(&r.abs(), &r_exp);
+
+        assert_eq!(test_matrix_2().range_space(-1.0).shape(), (4, 4));
     }
 
     #[test]
-    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
-    fn test_elementwise_mul_matrix_panic() {
-        DoubleMatrix::new_random(3, 4).mul_matrix(&DoubleMatrix::new_random(2, 3));
+    fn test_lu_matrix_1() {
+        let a = test_matrix_1();
+        let lu = a.lu();
+        let (p, l, u) = (lu.p(), lu.l(), lu.u());
+        assert_eq!(p.shape(), (3, 3));
+        assert_eq!(l.shape(), (3, 3));
+        assert_eq!(u.shape(), (3, 4));
+        assert!(!lu.is_singular());
+        assert_matrix(&p.mmul(&l).mmul(&u), &a);
     }
 
     #[test]
-    fn test_elementwise_div_scalar() {
-        let matrix = DoubleMatrix::ones(4, 5);
-        let res = matrix.div_scalar(0.5);
-        assert_matrix(&res, &matrix.mul_scalar(2f64));
+    fn test_lu_pivots() {
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            0.0, 1.0,
+            1.0, 0.0
+        ]);
+        let lu = a.lu();
+        assert_eq!(lu.pivots(), &[2, 2]);
+        assert_matrix(&lu.p(), &a);
+        assert_eq_f64(lu.determinant(), -1.0);
     }
 
     #[test]
-    fn test_elementwise_div_matrix() {
-        let matrix = DoubleMatrix::new_random(4, 5);
-        let res = matrix.div_matrix(&matrix);
-        assert_matrix(&res, &DoubleMatrix::ones(4, 5));
+    fn test_determinant_matrix_2() {
+        assert_eq_f64(test_matrix_2().determinant(), 24.0);
+        assert_eq_f64(DoubleMatrix::identity(3, 3).determinant(), 1.0);
     }
 
     #[test]
-    #[should_panic(expected = "Shape mismatch: (3, 4) != (2, 3).")]
-    fn test_elementwise_div_matrix_panic() {
-        DoubleMatrix::new_random(3, 4).div_matrix(&DoubleMatrix::new_random(2, 3));
+    fn test_determinant_singular() {
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            1.0, 2.0,
+            2.0, 4.0
+        ]);
+        assert!(a.lu().is_singular());
+        assert_eq_f64(a.determinant(), 0.0);
     }
 
     #[test]
-    fn test_column_sums() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(1, 4, vec![1.38, 1.43, 0.92, 1.08]);
-        assert_matrix(&matrix.column_sums(), &exp);
-
-        // row vector
-        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
-        assert_matrix(&matrix.column_sums(), &matrix);
-
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![1.0, 2.0, 3.0]);
-        let exp = DoubleMatrix::new(1, 1, vec![6.0]);
-        assert_matrix(&matrix.column_sums(), &exp);
-
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
-        assert_matrix(&matrix.column_sums(), &matrix);
+    #[should_panic(expected = "Matrix is not square: (3, 4).")]
+    fn test_determinant_not_square() {
+        test_matrix_1().determinant();
     }
 
     #[test]
-    fn test_column_mins() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(1, 4, vec![0.25, 0.16, 0.03, 0.23]);
-        assert_matrix(&matrix.column_mins(), &exp);
-
-        // row vector
-        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
-        assert_matrix(&matrix.column_mins(), &matrix);
-
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![2.0, 1.0, 3.0]);
-        let exp = DoubleMatrix::new(1, 1, vec![1.0]);
-        assert_matrix(&matrix.column_mins(), &exp);
+    fn test_solve_matrix_2() {
+        let a = test_matrix_2();
+        let b = DoubleMatrix::from_row_slice(4, 1, &[1.0, 2.0, 3.0, 4.0]);
+        let x = a.solve(&b);
+        let x_exp = DoubleMatrix::from_row_slice(4, 1, &[1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix(&x, &x_exp);
 
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
-        assert_matrix(&matrix.column_mins(), &matrix);
+        // multiple right-hand sides
+        let b = test_matrix_2().transpose();
+        let x = a.solve(&b);
+        assert_matrix(&a.mmul(&x), &b);
     }
 
     #[test]
-    fn test_column_maxs() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(1, 4, vec![0.71, 0.94, 0.52, 0.58]);
-        assert_matrix(&matrix.column_maxs(), &exp);
-
-        // row vector
-        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
-        assert_matrix(&matrix.column_mins(), &matrix);
-
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![2.0, 1.0, 3.0]);
-        let exp = DoubleMatrix::new(1, 1, vec![3.0]);
-        assert_matrix(&matrix.column_maxs(), &exp);
-
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
-        assert_matrix(&matrix.column_maxs(), &matrix);
+    #[should_panic(expected = "Dimensions mismatch for solve: 3 (rows of b) != 4 (n).")]
+    fn test_solve_dimensions_mismatch() {
+        test_matrix_2().solve(&DoubleMatrix::ones(3, 1));
     }
 
     #[test]
-    fn test_column_means() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(1, 4, vec![0.46, 0.47666667, 0.30666667, 0.36]);
-        assert_matrix(&matrix.column_means(), &exp);
-
-        // row vector
-        let matrix = DoubleMatrix::new(1, 3, vec![1.0, 2.0, 3.0]);
-        assert_matrix(&matrix.column_means(), &matrix);
+    #[should_panic(expected = "Matrix is singular: U(1, 1) is exactly zero, cannot solve or invert.")]
+    fn test_solve_singular() {
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            1.0, 2.0,
+            2.0, 4.0
+        ]);
+        a.solve(&DoubleMatrix::ones(2, 1));
+    }
 
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![21.0, 12.0, 36.0]);
-        let exp = DoubleMatrix::new(1, 1, vec![23.0]);
-        assert_matrix(&matrix.column_means(), &exp);
+    #[test]
+    fn test_inverse_matrix_2() {
+        let a = test_matrix_2();
+        let inv = a.inverse();
+        let inv_exp = DoubleMatrix::from_row_slice(4, 4, &[
+            1.0, -0.5, 1.0 / 6.0, -1.0 / 24.0,
+            0.0, 0.5, -1.0 / 6.0, 1.0 / 24.0,
+            0.0, 0.0, 1.0 / 3.0, -1.0 / 12.0,
+            0.0, 0.0, 0.0, 0.25
+        ]);
+        assert_matrix(&inv, &inv_exp);
+        assert_matrix(&a.mmul(&inv), &DoubleMatrix::identity(4, 4));
+    }
 
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![3.0]);
-        assert_matrix(&matrix.column_means(), &matrix);
+    #[test]
+    #[should_panic(expected = "Matrix is not square: (3, 4).")]
+    fn test_inverse_not_square() {
+        test_matrix_1().inverse();
     }
 
     #[test]
-    fn test_row_sums() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(3, 1, vec![0.67, 1.54, 2.6]);
-        assert_matrix(&matrix.row_sums(), &exp);
+    fn test_qr_matrix_1() {
+        let a = test_matrix_1();
+        let qr = a.qr();
+        assert_eq!(qr.q.shape(), (3, 3));
+        assert_eq!(qr.r.shape(), (3, 4));
+        assert!(qr.p.is_none());
+        assert_matrix(&qr.q.transpose().mmul(&qr.q), &DoubleMatrix::identity(3, 3));
+        assert_matrix(&qr.q.mmul(&qr.r), &a);
+        for j in 0..3 {
+            for i in j + 1..3 {
+                assert_eq!(qr.r.get(i, j), 0.0);
+            }
+        }
+    }
 
-        // row vector
-        let matrix = DoubleMatrix::new(1, 4, vec![0.25, 0.16, 0.03, 0.23]);
-        let exp = DoubleMatrix::new(1, 1, vec![0.67]);
-        assert_matrix(&matrix.row_sums(), &exp);
+    #[test]
+    fn test_qr_matrix_4() {
+        let a = test_matrix_4();
+        let qr = a.qr();
+        assert_eq!(qr.q.shape(), (3, 2));
+        assert_eq!(qr.r.shape(), (2, 2));
+        assert_eq!(qr.r.get(1, 0), 0.0);
+        assert_matrix(&qr.q.transpose().mmul(&qr.q), &DoubleMatrix::identity(2, 2));
+        assert_matrix(&qr.q.mmul(&qr.r), &a);
+    }
 
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
-        assert_matrix(&matrix.row_sums(), &matrix);
+    #[test]
+    fn test_qr_pivoted_matrix_4() {
+        let a = test_matrix_4();
+        let qr = a.qr_pivoted();
+        let p = qr.p.unwrap();
+        // second column has larger norm and is selected first
+        let p_exp = DoubleMatrix::from_row_slice(2, 2, &[
+            0.0, 1.0,
+            1.0, 0.0
+        ]);
+        assert_matrix(&p, &p_exp);
+        assert!(qr.r.get(0, 0).abs() >= qr.r.get(1, 1).abs());
+        assert_matrix(&qr.q.mmul(&qr.r), &a.mmul(&p));
+    }
 
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
-        assert_matrix(&matrix.row_sums(), &matrix);
+    #[test]
+    fn test_lstsq_overdetermined() {
+        let a = DoubleMatrix::from_row_slice(3, 2, &[
+            1.0, 0.0,
+            1.0, 1.0,
+            1.0, 2.0
+        ]);
+        let b = DoubleMatrix::from_row_slice(3, 1, &[1.0, 2.0, 2.0]);
+        let x_exp = DoubleMatrix::from_row_slice(2, 1, &[7.0 / 6.0, 0.5]);
+        let res_exp = DoubleMatrix::from_row_slice(1, 1, &[6f64.sqrt() / 6.0]);
+
+        let ls = a.lstsq(&b, -1.0);
+        assert_matrix(&ls.x, &x_exp);
+        assert_eq!(ls.rank, 2);
+        assert_matrix(&ls.residuals, &res_exp);
+        assert_eq!(ls.s.unwrap().shape(), (2, 1));
+
+        let ls = a.lstsq_qr(&b);
+        assert_matrix(&ls.x, &x_exp);
+        assert_eq!(ls.rank, 2);
+        assert_matrix(&ls.residuals, &res_exp);
+        assert!(ls.s.is_none());
     }
 
     #[test]
-    fn test_row_mins() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(3, 1, vec![0.03, 0.27, 0.37]);
-        assert_matrix(&matrix.row_mins(), &exp);
+    fn test_lstsq_rank_deficient() {
+        let a = DoubleMatrix::ones(3, 2);
+        let b = DoubleMatrix::from_row_slice(3, 1, &[1.0, 2.0, 3.0]);
+        let ls = a.lstsq(&b, -1.0);
+        // minimum norm solution
+        assert_matrix(&ls.x, &DoubleMatrix::ones(2, 1));
+        assert_eq!(ls.rank, 1);
+        assert_matrix(&ls.residuals, &DoubleMatrix::new(1, 1, vec![2f64.sqrt()]));
+    }
 
-        // row vector
-        let matrix = DoubleMatrix::new(1, 3, vec![0.1, 0.2, 0.3]);
-        let exp = DoubleMatrix::new(1, 1, vec![0.1]);
-        assert_matrix(&matrix.row_mins(), &exp);
+    #[test]
+    fn test_lstsq_underdetermined() {
+        let a = DoubleMatrix::ones(1, 2);
+        let b = DoubleMatrix::new(1, 2, vec![2.0, 4.0]);
+        let x_exp = DoubleMatrix::from_row_slice(2, 2, &[
+            1.0, 2.0,
+            1.0, 2.0
+        ]);
+        let ls = a.lstsq(&b, -1.0);
+        assert_matrix(&ls.x, &x_exp);
+        assert_eq!(ls.rank, 1);
+        assert_matrix(&ls.residuals, &DoubleMatrix::zeros(2, 1));
 
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
-        assert_matrix(&matrix.row_mins(), &matrix);
+        let ls = a.lstsq_qr(&b);
+        assert_matrix(&ls.x, &x_exp);
+    }
 
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
-        assert_matrix(&matrix.row_mins(), &matrix);
+    #[test]
+    #[should_panic(expected = "Matrix does not have full rank: R(1, 1) is exactly zero")]
+    fn test_lstsq_qr_rank_deficient() {
+        let a = DoubleMatrix::from_row_slice(3, 2, &[
+            1.0, 0.0,
+            1.0, 0.0,
+            1.0, 0.0
+        ]);
+        a.lstsq_qr(&DoubleMatrix::ones(3, 1));
     }
 
     #[test]
-    fn test_row_maxs() {
-        // full matrix
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(3, 1, vec![0.25, 0.52, 0.94]);
-        assert_matrix(&matrix.row_maxs(), &exp);
+    #[should_panic(expected = "Dimensions mismatch for least squares: 2 (rows of b) != 3 (rows of a).")]
+    fn test_lstsq_dimensions_mismatch() {
+        test_matrix_4().lstsq(&DoubleMatrix::ones(2, 1), -1.0);
+    }
 
-        // row vector
-        let matrix = DoubleMatrix::new(1, 3, vec![0.1, 0.2, 0.3]);
-        let exp = DoubleMatrix::new(1, 1, vec![0.3]);
-        assert_matrix(&matrix.row_maxs(), &exp);
+    fn test_spd_matrix() -> DoubleMatrix {
+        DoubleMatrix::from_row_slice(3, 3, &[
+            4.0, 2.0, 2.0,
+            2.0, 5.0, 3.0,
+            2.0, 3.0, 6.0
+        ])
+    }
 
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
-        assert_matrix(&matrix.row_maxs(), &matrix);
+    #[test]
+    fn test_cholesky() {
+        let a = test_spd_matrix();
+        let l = a.cholesky();
+        let l_exp = DoubleMatrix::from_row_slice(3, 3, &[
+            2.0, 0.0, 0.0,
+            1.0, 2.0, 0.0,
+            1.0, 1.0, 2.0
+        ]);
+        assert_matrix(&l, &l_exp);
+        assert_matrix(&l.mmul(&l.transpose()), &a);
+    }
 
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
-        assert_matrix(&matrix.row_maxs(), &matrix);
+    #[test]
+    #[should_panic(expected = "Matrix is not positive definite: leading minor of order 2 is not \
+        positive.")]
+    fn test_cholesky_not_positive_definite() {
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            1.0, 2.0,
+            2.0, 1.0
+        ]);
+        a.cholesky();
     }
 
     #[test]
-    fn test_row_means() {
-        let matrix = test_matrix_1();
-        let exp = DoubleMatrix::new(3, 1, vec![0.1675, 0.385, 0.65]);
-        assert_matrix(&matrix.row_means(), &exp);
+    fn test_try_cholesky() {
+        assert!(test_spd_matrix().try_cholesky().is_ok());
+        assert_eq!(test_matrix_1().try_cholesky().unwrap_err(),
+            MatrixError::ShapeMismatch("Matrix is not square: (3, 4).".to_string()));
+        assert_eq!(DoubleMatrix::zeros(2, 2).try_cholesky().unwrap_err(), MatrixError::Singular(
+            "Matrix is not positive definite: leading minor of order 1 is not positive."
+                .to_string(), 1));
+        let b = DoubleMatrix::ones(2, 1);
+        assert_eq!(DoubleMatrix::zeros(2, 2).try_cholesky_solve(&b).unwrap_err().info(), Some(1));
+    }
 
-        // row vector
-        let matrix = DoubleMatrix::new(1, 4, vec![0.71, 0.94, 0.37, 0.58]);
-        let exp = DoubleMatrix::new(1, 1, vec![0.65]);
-        assert_matrix(&matrix.row_means(), &exp);
+    #[test]
+    fn test_cholesky_solve() {
+        let a = test_spd_matrix();
+        let b = DoubleMatrix::from_row_slice(3, 2, &[
+            8.0, 4.0,
+            10.0, 2.0,
+            11.0, 2.0
+        ]);
+        let x = a.cholesky_solve(&b);
+        let x_exp = DoubleMatrix::from_row_slice(3, 2, &[
+            1.0, 1.0,
+            1.0, 0.0,
+            1.0, 0.0
+        ]);
+        assert_matrix(&x, &x_exp);
+        assert!(a.try_cholesky_solve(&DoubleMatrix::ones(2, 1)).is_err());
+    }
 
-        // column vector
-        let matrix = DoubleMatrix::new(3, 1, vec![0.1, 0.2, 0.3]);
-        assert_matrix(&matrix.row_means(), &matrix);
+    #[test]
+    fn test_is_positive_definite() {
+        assert!(test_spd_matrix().is_positive_definite());
+        assert!(DoubleMatrix::identity(4, 4).is_positive_definite());
+        assert!(!test_matrix_1().is_positive_definite());
+        assert!(!DoubleMatrix::ones(3, 3).is_positive_definite());
+        // not symmetric, but lower triangle is positive definite
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            4.0, 1.0,
+            0.0, 4.0
+        ]);
+        assert!(!a.is_positive_definite());
+    }
 
-        // single element
-        let matrix = DoubleMatrix::new(1, 1, vec![0.43]);
-        assert_matrix(&matrix.row_means(), &matrix);
+    fn test_symmetric_matrix() -> DoubleMatrix {
+        // eigenvalues are 2 - sqrt(2), 2 and 2 + sqrt(2)
+        DoubleMatrix::from_row_slice(3, 3, &[
+            2.0, -1.0, 0.0,
+            -1.0, 2.0, -1.0,
+            0.0, -1.0, 2.0
+        ])
     }
 
-    #[test]
-    #[should_panic(expected = "Invalid shape for column vector: (4, 5).")]
-    fn test_put_get_column_invalid_shape() {
-        let mut matrix = test_matrix_1();
-        let (rows, cols) = matrix.shape();
-        matrix.put_column(1, &DoubleMatrix::ones(rows + 1, cols + 1));
+    // Check that A * V = V * diag(w) and columns of V are orthonormal
+    fn assert_eigen_pairs(a: &DoubleMatrix, eigen: &Eigen) {
+        let (v, w) = (&eigen.vectors, &eigen.values);
+        let av = a.mmul(v);
+        for j in 0..w.rows() {
+            for i in 0..a.rows() {
+                assert_eq_f64(av.get(i, j), w.get(j, 0) * v.get(i, j));
+            }
+        }
+        assert_matrix(&v.transpose().mmul(v), &DoubleMatrix::identity(w.rows(), w.rows()));
     }
 
     #[test]
-    #[should_panic(expected = "Invalid column index 4 (4 columns).")]
-    fn test_put_get_column_invalid_index_1() {
-        let mut matrix = test_matrix_1();
-        let (rows, cols) = matrix.shape();
-        matrix.put_column(cols, &DoubleMatrix::ones(rows, 1));
+    fn test_eigh() {
+        let a = test_symmetric_matrix();
+        let eigen = a.eigh();
+        let w_exp = DoubleMatrix::from_row_slice(3, 1, &[
+            2.0 - 2f64.sqrt(), 2.0, 2.0 + 2f64.sqrt()
+        ]);
+        assert_matrix(&a, &test_symmetric_matrix());
+        assert_matrix(&eigen.values, &w_exp);
+        assert_eq!(eigen.vectors.shape(), (3, 3));
+        assert_eigen_pairs(&a, &eigen);
     }
 
     #[test]
-    #[should_panic(expected = "Invalid column index 4 (4 columns).")]
-    fn test_put_get_column_invalid_index_2() {
-        let matrix = test_matrix_1();
-        matrix.get_column(matrix.cols());
+    #[should_panic(expected = "Matrix is not square: (3, 4).")]
+    fn test_eigh_not_square() {
+        test_matrix_1().eigh();
     }
 
     #[test]
-    fn test_put_get_column() {
-        let mut matrix = test_matrix_1();
-        let (rows, cols) = matrix.shape();
-        let vector = DoubleMatrix::ones(rows, 1);
-        matrix.put_column(1, &vector);
-        assert_matrix(&matrix.get_column(1), &vector);
-        for col in 0..cols {
-            matrix.put_column(col, &vector);
-        }
-        assert_matrix(&matrix, &DoubleMatrix::ones(rows, cols));
+    fn test_eigh_index_range() {
+        let a = test_symmetric_matrix();
+        let eigen = a.eigh_range(EigenRange::Index(1, 3));
+        let w_exp = DoubleMatrix::from_row_slice(2, 1, &[2.0, 2.0 + 2f64.sqrt()]);
+        assert_matrix(&eigen.values, &w_exp);
+        assert_eq!(eigen.vectors.shape(), (3, 2));
+        assert_eigen_pairs(&a, &eigen);
     }
 
-    #[test]
-    #[should_panic(expected = "Invalid shape for row vector: (4, 5).")]
-    fn test_put_get_row_invalid_shape() {
-        let mut matrix = test_matrix_1();
-        let (rows, cols) = matrix.shape();
-        matrix.put_row(1, &DoubleMatrix::ones(rows + 1, cols + 1));
+    #[test]
+    fn test_eigh_value_range() {
+        let a = test_symmetric_matrix();
+        let eigen = a.eigh_range(EigenRange::Value(1.0, ::std::f64::INFINITY));
+        let w_exp = DoubleMatrix::from_row_slice(2, 1, &[2.0, 2.0 + 2f64.sqrt()]);
+        assert_matrix(&eigen.values, &w_exp);
+        assert_eigen_pairs(&a, &eigen);
+
+        let eigen = a.eigh_range(EigenRange::Value(::std::f64::NEG_INFINITY, 1.0));
+        assert_matrix(&eigen.values, &DoubleMatrix::new(1, 1, vec![2.0 - 2f64.sqrt()]));
+
+        let eigen = a.eigh_range(EigenRange::Value(5.0, 10.0));
+        assert_eq!(eigen.values.shape(), (0, 1));
+        assert_eq!(eigen.vectors.shape(), (3, 0));
+
+        // finite bound is beyond the bound that replaces infinite one
+        let eigen = a.eigh_range(EigenRange::Value(100.0, ::std::f64::INFINITY));
+        assert_eq!(eigen.values.shape(), (0, 1));
+        assert_eq!(eigen.vectors.shape(), (3, 0));
+
+        let eigen = a.eigh_range(EigenRange::Value(::std::f64::NEG_INFINITY, -100.0));
+        assert_eq!(eigen.values.shape(), (0, 1));
+        assert_eq!(eigen.vectors.shape(), (3, 0));
     }
 
     #[test]
-    #[should_panic(expected = "Invalid row index 3 (3 rows).")]
-    fn test_put_get_row_invalid_index_1() {
-        let mut matrix = test_matrix_1();
-        let (rows, cols) = matrix.shape();
-        matrix.put_row(rows, &DoubleMatrix::ones(1, cols));
+    #[should_panic(expected = "Invalid range of eigenvalues: [0, 4) for 3 values.")]
+    fn test_eigh_index_range_invalid() {
+        test_symmetric_matrix().eigh_range(EigenRange::Index(0, 4));
     }
 
     #[test]
-    #[should_panic(expected = "Invalid row index 3 (3 rows).")]
-    fn test_put_get_row_invalid_index_2() {
-        let matrix = test_matrix_1();
-        matrix.get_row(matrix.rows());
+    fn test_eigh_top() {
+        let a = test_spd_matrix();
+        let eigen = a.eigh_top(2);
+        let full = a.eigh();
+        let w_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            full.values.get(2, 0), full.values.get(1, 0)
+        ]);
+        assert_matrix(&eigen.values, &w_exp);
+        assert_eq!(eigen.vectors.shape(), (3, 2));
+        assert_eigen_pairs(&a, &eigen);
     }
 
     #[test]
-    fn test_put_get_row() {
-        let mut matrix = test_matrix_1();
-        let (rows, cols) = matrix.shape();
-        let vector = DoubleMatrix::ones(1, cols);
-        matrix.put_row(1, &vector);
-        assert_matrix(&matrix.get_row(1), &vector);
-        for row in 0..rows {
-            matrix.put_row(row, &vector);
+    #[should_panic(expected = "Invalid number of eigenvalues: 4.")]
+    fn test_eigh_top_invalid_k() {
+        test_spd_matrix().eigh_top(4);
+    }
+
+    // Check that A * v(j) = w(j) * v(j) for complex eigenvalues and right eigenvectors
+    fn assert_right_eigen_pairs(a: &DoubleMatrix, w: &ComplexMatrix, v: &ComplexMatrix) {
+        let (avr, avi) = (a.mmul(&v.re), a.mmul(&v.im));
+        for j in 0..a.cols() {
+            let (wr, wi) = (w.re.get(j, 0), w.im.get(j, 0));
+            for i in 0..a.rows() {
+                let (vr, vi) = (v.re.get(i, j), v.im.get(i, j));
+                assert_eq_f64(avr.get(i, j), wr * vr - wi * vi);
+                assert_eq_f64(avi.get(i, j), wr * vi + wi * vr);
+            }
         }
-        assert_matrix(&matrix, &DoubleMatrix::ones(rows, cols));
     }
 
     #[test]
-    fn test_matrix_min() {
-        assert_eq!(test_matrix_1().min(), 0.03);
-        assert_eq!(test_matrix_2().min(), 0.0);
-        assert_eq!(test_matrix_3().min(), 1.0);
-        assert_eq!(test_matrix_4().min(), 1.0);
+    fn test_eig_complex() {
+        // rotation matrix has eigenvalues i and -i
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            0.0, -1.0,
+            1.0, 0.0
+        ]);
+        let eig = a.eig();
+        assert_matrix(&eig.values.re, &DoubleMatrix::zeros(2, 1));
+        assert_matrix(&eig.values.im, &DoubleMatrix::from_row_slice(2, 1, &[1.0, -1.0]));
+        assert!(eig.left.is_none());
+        let right = eig.right.unwrap();
+        assert_eq!(right.re.shape(), (2, 2));
+        assert_eq!(right.im.shape(), (2, 2));
+        // second eigenvector is complex conjugate of the first one
+        assert_eq_f64(right.re.get(0, 0), right.re.get(0, 1));
+        assert_eq_f64(right.im.get(1, 0), -right.im.get(1, 1));
+        assert_right_eigen_pairs(&a, &eig.values, &right);
     }
 
     #[test]
-    fn test_matrix_max() {
-        assert_eq!(test_matrix_1().max(), 0.94);
-        assert_eq!(test_matrix_2().max(), 4.0);
-        assert_eq!(test_matrix_3().max(), 8.0);
-        assert_eq!(test_matrix_4().max(), 5.0);
+    fn test_eig_markov_chain() {
+        // transition matrix with eigenvalues 1 and 0.4
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            0.9, 0.1,
+            0.5, 0.5
+        ]);
+        let eig = a.eig_with(true, true);
+        let mut values = eig.values.re.data().to_vec();
+        values.sort_by(|x, y| y.partial_cmp(x).unwrap());
+        assert_eq_f64(values[0], 1.0);
+        assert_eq_f64(values[1], 0.4);
+        assert_matrix(&eig.values.im, &DoubleMatrix::zeros(2, 1));
+        let right = eig.right.unwrap();
+        assert_matrix(&right.im, &DoubleMatrix::zeros(2, 2));
+        assert_right_eigen_pairs(&a, &eig.values, &right);
+
+        // left eigenvectors of A are right eigenvectors of A^T, stationary distribution is
+        // proportional to [5, 1]
+        let left = eig.left.unwrap();
+        assert_right_eigen_pairs(&a.transpose(), &eig.values, &left);
+        let j = if eig.values.re.get(0, 0) > eig.values.re.get(1, 0) { 0 } else { 1 };
+        assert_eq_f64(left.re.get(0, j) / left.re.get(1, j), 5.0);
     }
 
     #[test]
-    fn test_matrix_sum() {
-        assert_eq!(test_matrix_1().sum(), 4.81);
-        assert_eq!(test_matrix_2().sum(), 13.0);
-        assert_eq!(test_matrix_3().sum(), 36.0);
-        assert_eq!(test_matrix_4().sum(), 17.0);
+    fn test_eig_values_only() {
+        let eig = test_matrix_2().eig_with(false, false);
+        // order of eigenvalues is not specified
+        let mut values = eig.values.re.data().to_vec();
+        values.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        assert_matrix(&DoubleMatrix::new(4, 1, values),
+            &DoubleMatrix::from_row_slice(4, 1, &[1.0, 2.0, 3.0, 4.0]));
+        assert_matrix(&eig.values.im, &DoubleMatrix::zeros(4, 1));
+        assert!(eig.left.is_none());
+        assert!(eig.right.is_none());
     }
 
     #[test]
-    fn test_norm1() {
-        assert_eq_f64(test_matrix_1().norm1(), 4.81);
-        assert_eq_f64(test_matrix_2().norm1(), 13.0);
-        assert_eq_f64(test_matrix_3().norm1(), 36.0);
-        assert_eq_f64(test_matrix_4().norm1(), 17.0);
+    #[should_panic(expected = "Matrix is not square: (3, 4).")]
+    fn test_eig_not_square() {
+        test_matrix_1().eig();
     }
 
     #[test]
-    fn test_norm2() {
-        assert_eq_f64(test_matrix_1().norm2(), 1.622189877911954);
-        assert_eq_f64(test_matrix_2().norm2(), 5.744562646538029);
-        assert_eq_f64(test_matrix_3().norm2(), 14.2828568570857);
-        assert_eq_f64(test_matrix_4().norm2(), 7.681145747868608);
+    fn test_lansvd_with_options() {
+        let a = test_matrix_2();
+        let options = LanczosOptions::new()
+            .tolerance(1e-12)
+            .max_restarts(10)
+            .dim(4)
+            .shifts(1)
+            .classical_gs(true)
+            .extended_local_orth(false);
+        let svd = a.lansvd_with(2, &options);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+        assert_matrix(&a, &test_matrix_2());
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
     }
 
     #[test]
-    fn test_transpose() {
-        assert_matrix(&test_matrix_1().transpose().transpose(), &test_matrix_1());
-        assert_matrix(&test_matrix_2().transpose().transpose(), &test_matrix_2());
-        assert_matrix(&test_matrix_3().transpose().transpose(), &test_matrix_3());
-        assert_matrix(&test_matrix_4().transpose().transpose(), &test_matrix_4());
+    fn test_lansvd_result() {
+        let a = test_matrix_2();
+        let res = a.lansvd_result(2, &LanczosOptions::new());
+        assert_eq!(res.status, LanczosStatus::Converged);
+        assert_eq!(res.status.info(), 0);
+        assert_eq!(res.requested, 2);
+        assert_eq!(res.converged(), 2);
+        assert!(res.matvecs > 0);
+        assert_eq!(res.bnd.shape(), (2, 1));
+        for &bnd in res.bnd.data() {
+            assert!(bnd >= 0.0 && bnd <= 1e-6, "Invalid error bound {}", bnd);
+        }
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            4.260007, 3.107349
+        ]);
+        let svd = res.into_svd();
+        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
     }
 
     #[test]
-    #[should_panic(expected = "Unable to transpose a non-square matrix in-place.")]
-    fn test_transpose_mut_panic() {
-        let mut matrix = test_matrix_1();
-        matrix.transpose_mut();
+    fn test_lanczos_status() {
+        assert_eq!(LanczosStatus::Converged.info(), 0);
+        assert_eq!(LanczosStatus::NotConverged.info(), -1);
+        assert_eq!(LanczosStatus::InvariantSubspace(3).info(), 3);
     }
 
     #[test]
-    fn test_transpose_mut() {
-        let matrix = DoubleMatrix::new_random(3, 3);
-        let mut clone = matrix.clone();
-        clone.transpose_mut();
-        assert_matrix(&clone, &matrix.transpose());
+    fn test_lansvd_result_not_converged() {
+        // without restarts DLANSVD_IRL returns before any triplet converges
+        let options = LanczosOptions::new().tolerance(1e-15).max_restarts(0);
+        let res = test_matrix_2().lansvd_result(2, &options);
+        assert_eq!(res.status, LanczosStatus::NotConverged);
+        assert_eq!(res.status.info(), -1);
+        assert_eq!(res.requested, 2);
+        assert!(res.converged() < 2);
+        assert_eq!(res.restarts, 0);
+        assert!(res.svd.u.is_none());
+        assert!(res.svd.v.is_none());
+        let err = res.try_into_svd().unwrap_err();
+        assert_eq!(err.info(), Some(-1));
+        assert!(err.message().starts_with(
+            "DLANSVD_IRL, K singular triplets did not converge within KMAX iterations"));
     }
 
     #[test]
-    #[should_panic(expected = "Unable to get the diagonal of a non-square matrix.")]
-    fn test_diag_panic() {
-        test_matrix_1().diag();
+    fn test_lansvd_result_try_into_svd() {
+        let res = LanczosSvd {
+            svd: SVD { u: None, s: DoubleMatrix::ones(1, 1), v: None },
+            bnd: DoubleMatrix::zeros(1, 1),
+            requested: 2,
+            restarts: 0,
+            matvecs: 10,
+            status: LanczosStatus::InvariantSubspace(1)
+        };
+        let err = res.try_into_svd().unwrap_err();
+        assert_eq!(err.message(),
+            "DLANSVD_IRL, an invariant subspace of dimension J was found, 1.");
+        assert_eq!(err.info(), Some(1));
     }
 
     #[test]
-    fn test_diag() {
-        let matrix = DoubleMatrix::identity(4, 4);
-        assert_matrix(&matrix.diag(), &DoubleMatrix::new(4, 1, vec![1.0, 1.0, 1.0, 1.0]));
+    fn test_try_shape_mismatch() {
+        let a = test_matrix_1();
+        let err = a.try_add_matrix(&test_matrix_2()).unwrap_err();
+        assert_eq!(err,
+            MatrixError::ShapeMismatch("Shape mismatch: (3, 4) != (4, 4).".to_string()));
+        assert_eq!(err.info(), None);
+        assert!(a.try_mul_matrix(&test_matrix_2()).is_err());
+        assert!(a.try_mmul(&test_matrix_1()).is_err());
+        assert!(a.try_mv(&DoubleMatrix::ones(3, 1)).is_err());
+        assert!(a.try_dot(&test_matrix_2()).is_err());
+        assert!(DoubleMatrix::try_new(2, 2, vec![1.0; 3]).is_err());
+        assert_matrix(&a.try_mmul(&test_matrix_2()).unwrap(), &a.mmul(&test_matrix_2()));
     }
 
     #[test]
-    fn test_abs() {
-        assert_matrix(&test_matrix_1().abs(), &test_matrix_1());
+    fn test_try_index_out_of_bounds() {
+        let mut a = test_matrix_1();
+        assert_eq!(a.try_get(2, 3), Ok(a.get(2, 3)));
+        assert_eq!(a.try_get(3, 0).unwrap_err(),
+            MatrixError::IndexOutOfBounds("Invalid index (3, 0) for shape (3, 4).".to_string()));
+        assert!(a.try_put(0, 4, 1.0).is_err());
+        assert_eq!(a.try_get_column(4).unwrap_err(),
+            MatrixError::IndexOutOfBounds("Invalid column index 4 (4 columns).".to_string()));
+        assert!(a.try_get_row(3).is_err());
+    }
 
-        let matrix = DoubleMatrix::new(3, 2, vec![
-            -1.0, 2.0, -3.0,
-            4.0, -5.0, -6.0
-        ]);
-        let exp = DoubleMatrix::new(3, 2, vec![
-            1.0, 2.0, 3.0,
-            4.0, 5.0, 6.0
+    #[test]
+    fn test_try_invalid_argument() {
+        let a = test_matrix_1();
+        assert_eq!(a.try_svd(0).unwrap_err(),
+            MatrixError::InvalidArgument("Invalid number of singular values: 0.".to_string()));
+        assert!(a.try_svd_range(SvdRange::Index(2, 1), SvdJob::Values).is_err());
+        assert!(a.try_lansvd_pro(4, 10).is_err());
+        assert!(a.try_eigh().is_err());
+        assert!(SvdJob::try_from_char('X').is_err());
+        let svd = a.try_svd_job(2, SvdJob::Values).unwrap();
+        assert!(svd.try_reconstruct().is_err());
+    }
+
+    #[test]
+    fn test_try_singular() {
+        let a = DoubleMatrix::from_row_slice(2, 2, &[
+            1.0, 2.0,
+            2.0, 4.0
         ]);
-        assert_matrix(&matrix.abs(), &exp);
+        let err = a.try_solve(&DoubleMatrix::ones(2, 1)).unwrap_err();
+        assert_eq!(err, MatrixError::Singular(
+            "Matrix is singular: U(1, 1) is exactly zero, cannot solve or invert.".to_string(), 2));
+        assert_eq!(err.info(), Some(2));
+        assert!(a.try_inverse().is_err());
+        assert_eq!(a.try_determinant(), Ok(0.0));
     }
 
     #[test]
-    fn test_full_svd_test_matrix_2() {
-        let a = test_matrix_2();
-        let svd = a.full_svd();
+    fn test_try_allocation_failure() {
+        let err = DoubleMatrix::try_zeros(usize::MAX, 2).unwrap_err();
+        assert_eq!(err, MatrixError::AllocationFailure(
+            format!("Matrix is too large to allocate: {} * 2 elements.", usize::MAX)));
+        assert_eq!(DoubleMatrix::checked_len(3, 4), Ok(12));
+
+        assert!(DoubleMatrix::try_ones(usize::MAX, 2).is_err());
+        assert!(DoubleMatrix::try_identity(usize::MAX, 2).is_err());
+        assert!(DoubleMatrix::try_new_random(usize::MAX, 2).is_err());
+
+        // size is valid, but memory cannot be allocated
+        let rows = isize::MAX as usize / 16;
+        assert_eq!(DoubleMatrix::try_zeros(rows, 1).unwrap_err(), MatrixError::AllocationFailure(
+            format!("Failed to allocate matrix: {} * 1 elements.", rows)));
+    }
 
-        let u_exp = DoubleMatrix::from_row_slice(4, 4, &[
-            0.013543, -0.135435, 0.542638, 0.828866,
-            0.109341, -0.518419, 0.667345, -0.523390,
-            0.470163, -0.714229, -0.481962, 0.191143,
-            0.875676, 0.450306, 0.167053, -0.050094
-        ]);
-        let s_exp = DoubleMatrix::from_row_slice(4, 1, &[
-            4.260007, 3.107349, 2.111785, 0.858542
-        ]);
-        let v_exp = DoubleMatrix::from_row_slice(4, 4, &[
-            0.003179, -0.043585, 0.256957, 0.965434,
-            0.054513, -0.377258, 0.888977, -0.253819,
-            0.356767, -0.856391, -0.368665, 0.058285,
-            0.932596, 0.349815, 0.088195, -0.010752
+    #[test]
+    fn test_lansvd_smallest() {
+        let a = test_matrix_2();
+        let options = LanczosOptions::new().which(Which::Smallest).max_restarts(10);
+        let svd = a.lansvd_with(2, &options);
+        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
+            2.111785, 0.858542
         ]);
-
         assert_matrix(&a, &test_matrix_2());
-        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
         assert_matrix_eps(&svd.s, &s_exp, 1e-6);
-        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
     }
 
     #[test]
-    fn test_full_svd_test_matrix_3() {
-        let a = test_matrix_3();
-        let svd = a.full_svd();
+    fn test_lanczos_which() {
+        assert_eq!(Which::from_char('L'), Which::Largest);
+        assert_eq!(Which::from_char('s'), Which::Smallest);
+        assert_eq!(Which::Largest.as_char(), 'L');
+        assert_eq!(Which::Smallest.as_char(), 'S');
+    }
 
-        let u_exp = DoubleMatrix::from_row_slice(2, 2, &[
-            -0.376168, -0.926551,
-            -0.926551, 0.376168
-        ]);
-        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
-            14.227407, 1.257330
-        ]);
-        let v_exp = DoubleMatrix::from_row_slice(4, 4, &[
-            -0.352062, 0.758981, -0.400087, -0.374072,
-            -0.443626, 0.321242, 0.254633, 0.796971,
-            -0.535190, -0.116498, 0.690996, -0.471724,
-            -0.626754, -0.554238, -0.545542, 0.048826
-        ]);
+    #[test]
+    #[should_panic(expected = "Invalid singular values selector 'X', expected 'L' or 'S'.")]
+    fn test_lanczos_which_invalid() {
+        Which::from_char('X');
+    }
 
-        assert_matrix(&a, &test_matrix_3());
-        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
-        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
-        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+    #[test]
+    #[should_panic(expected = "Invalid dimension of Krylov subspace: 2 (k = 2).")]
+    fn test_lansvd_with_invalid_dim() {
+        test_matrix_2().lansvd_with(2, &LanczosOptions::new().dim(2));
     }
 
     #[test]
-    fn test_full_svd_test_matrix_4() {
-        let a = test_matrix_4();
-        let svd = a.full_svd();
+    #[should_panic(expected = "Invalid number of shifts per restart: 3 (dim = 4, k = 2).")]
+    fn test_lansvd_with_invalid_shifts() {
+        test_matrix_2().lansvd_with(2, &LanczosOptions::new().dim(4).shifts(3));
+    }
 
-        let u_exp = DoubleMatrix::from_row_slice(3, 3, &[
-            -0.291036, -0.339556, -0.894427,
-            -0.582071, -0.679112, 0.447214,
-            -0.759270, 0.650776, -0.000000
-        ]);
-        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
-            7.675619, 0.291321
-        ]);
-        let v_exp = DoubleMatrix::from_row_slice(2, 2, &[
-            -0.486344, 0.873768,
-            -0.873768, -0.486344
-        ]);
+    // Operator that represents 2 * A for matrix A
+    struct ScaledOperator(DoubleMatrix);
 
-        assert_matrix(&a, &test_matrix_4());
-        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
-        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
-        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+    impl LinearOperator for ScaledOperator {
+        fn shape(&self) -> (usize, usize) {
+            self.0.shape()
+        }
+
+        fn matvec(&self, x: &[f64], y: &mut [f64]) {
+            self.0.matvec(x, y);
+            for i in 0..y.len() {
+                y[i] *= 2.0;
+            }
+        }
+
+        fn rmatvec(&self, x: &[f64], y: &mut [f64]) {
+            self.0.rmatvec(x, y);
+            for i in 0..y.len() {
+                y[i] *= 2.0;
+            }
+        }
     }
 
-    #[test]
-    fn test_singular_values_matrix_2() {
-        let a = test_matrix_2();
-        let s = a.singular_values();
-        let s_exp = DoubleMatrix::from_row_slice(4, 1, &[
-            4.260007, 3.107349, 2.111785, 0.858542
-        ]);
-        assert_matrix_eps(&s, &s_exp, 1e-6);
+    // Operator that fails on transposed product
+    struct FailingOperator;
+
+    impl LinearOperator for FailingOperator {
+        fn shape(&self) -> (usize, usize) {
+            (4, 4)
+        }
+
+        fn matvec(&self, _x: &[f64], y: &mut [f64]) {
+            for i in 0..y.len() {
+                y[i] = 1.0;
+            }
+        }
+
+        fn rmatvec(&self, _x: &[f64], _y: &mut [f64]) {
+            panic!("Operator failure");
+        }
     }
 
-    #[test]
-    fn test_singular_values_matrix_3() {
-        let a = test_matrix_3();
-        let s = a.singular_values();
-        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
-            14.227407, 1.257330
-        ]);
-        assert_matrix_eps(&s, &s_exp, 1e-6);
+    // Operator that runs Lanczos SVD of the wrapped matrix in each matrix-vector product
+    struct NestedOperator(DoubleMatrix);
+
+    impl LinearOperator for NestedOperator {
+        fn shape(&self) -> (usize, usize) {
+            self.0.shape()
+        }
+
+        fn matvec(&self, x: &[f64], y: &mut [f64]) {
+            self.0.lansvd(1);
+            self.0.matvec(x, y);
+        }
+
+        fn rmatvec(&self, x: &[f64], y: &mut [f64]) {
+            self.0.lansvd(1);
+            self.0.rmatvec(x, y);
+        }
     }
 
     #[test]
-    fn test_svd_matrix_2() {
-        let a = test_matrix_2();
-        let svd = a.svd(2);
-
-        let u_exp = DoubleMatrix::from_row_slice(4, 2, &[
-            -0.013543, -0.135435,
-            -0.109341, -0.518419,
-            -0.470163, -0.714229,
-            -0.875676, 0.450306
-        ]);
+    fn test_lansvd_operator() {
+        let op = ScaledOperator(test_matrix_2());
+        let svd = lansvd_operator(&op, 2);
         let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
-            4.260007, 3.107349
-        ]);
-
-        let v_exp = DoubleMatrix::from_row_slice(4, 2, &[
-            -0.003179, -0.043585,
-            -0.054513, -0.377258,
-            -0.356767, -0.856391,
-            -0.932596, 0.349815
+            8.520014, 6.214698
         ]);
+        assert_matrix_eps(&svd.s, &s_exp, 1e-5);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.unwrap().shape(), (4, 2));
+    }
 
-        assert_matrix(&a, &test_matrix_2());
-        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
-        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
-        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+    #[test]
+    #[should_panic(expected = "Operator failure")]
+    fn test_lansvd_operator_panic() {
+        lansvd_operator(&FailingOperator, 2);
     }
 
     #[test]
-    fn test_lansvd_matrix_2() {
-        let a = test_matrix_2();
-        let svd = a.lansvd(2);
+    fn test_try_lansvd_operator_failure() {
+        match try_lansvd_operator(&FailingOperator, 2) {
+            Err(MatrixError::InvalidArgument(msg)) => {
+                assert_eq!(msg, "Linear operator failed: Operator failure")
+            },
+            res => panic!("Unexpected result {:?}", res.map(|svd| svd.s))
+        }
+    }
 
-        let u_exp = DoubleMatrix::from_row_slice(4, 2, &[
-            -0.013543, 0.135435,
-            -0.109341, 0.518419,
-            -0.470163, 0.714229,
-            -0.875676, -0.450306
-        ]);
-        let s_exp = DoubleMatrix::from_row_slice(2, 1, &[
-            4.260007, 3.107349
-        ]);
+    #[test]
+    fn test_try_lansvd_operator_nested() {
+        match try_lansvd_operator(&NestedOperator(test_matrix_2()), 2) {
+            Err(MatrixError::InvalidArgument(msg)) => {
+                assert_eq!(msg, "Linear operator failed: Lanczos SVD cannot be called from APROD \
+                    function or linear operator.")
+            },
+            res => panic!("Unexpected result {:?}", res.map(|svd| svd.s))
+        }
+        // lock and flag are released after failed call
+        assert!(test_matrix_2().try_lansvd_with(2, &LanczosOptions::new()).is_ok());
+    }
 
-        let v_exp = DoubleMatrix::from_row_slice(4, 2, &[
-            -0.003179, 0.043585,
-            -0.054513, 0.377258,
-            -0.356767, 0.856391,
-            -0.932596, -0.349815
-        ]);
+    #[test]
+    #[should_panic(expected = "Invalid length of input vector: 3 != 4.")]
+    fn test_matvec_invalid_input() {
+        let mut y = [0f64; 4];
+        test_matrix_2().matvec(&[1.0, 2.0, 3.0], &mut y);
+    }
 
-        assert_matrix(&a, &test_matrix_2());
-        assert_matrix_eps(&svd.u.unwrap(), &u_exp, 1e-6);
-        assert_matrix_eps(&svd.s, &s_exp, 1e-6);
-        assert_matrix_eps(&svd.v.unwrap(), &v_exp, 1e-6);
+    #[test]
+    #[should_panic(expected = "Invalid length of output vector: 3 != 4.")]
+    fn test_rmatvec_invalid_output() {
+        let mut y = [0f64; 3];
+        test_matrix_2().rmatvec(&[1.0, 2.0, 3.0, 4.0], &mut y);
     }
 
     #[test]
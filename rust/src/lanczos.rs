@@ -20,10 +20,17 @@
 
 //! Module that defines bindings and wrapper methods for Lanczos SVD.
 
+use std::any::Any;
+use std::cell::Cell;
+use std::panic;
+use std::ptr;
+use std::slice;
+use std::sync::Mutex;
 use libc::{c_char, c_double, c_int};
+use internal::{MatrixError, MatrixResult, SvdJob};
 
 #[allow(non_camel_case_types)]
-type PROPACK_EXTERN = Option<
+pub type PROPACK_EXTERN = Option<
     extern "C" fn(*const c_char, *const c_int, *const c_int, *const c_double, *mut c_double, *const c_double, *mut c_int)
 >;
 
@@ -43,7 +50,354 @@ pub extern "C" fn dense_matmul(
     }
 }
 
+// APROD for transpose of dense matrix A, `m` and `n` are dimensions of A^T and `dparm` contains
+// A in column-major order. Products with A^T are computed by swapping `transa` for A, so that
+// A is never copied.
+#[no_mangle]
+pub extern "C" fn transposed_dense_matmul(
+    transa: *const c_char,
+    m: *const c_int,
+    n: *const c_int,
+    x: *const c_double,
+    y: *mut c_double,
+    dparm: *const c_double,
+    _iparm: *mut c_int
+) {
+    unsafe {
+        let trans = if *transa as u8 == b'n' || *transa as u8 == b'N' { b'T' } else { b'N' };
+        // A has n rows and m columns, leading dimension is n
+        dgemv_(&(trans as c_char), n, m, &1f64, dparm, n, x, &1i32, &0f64, y, &1i32);
+    }
+}
+
+// APROD for sparse matrices in compressed sparse column (CSC) format.
+// `dparm` contains non-zero values, and `iparm` contains column pointers (n + 1 elements) followed
+// by row indices of non-zero values, see `SparseMatrix` for details.
+#[no_mangle]
+pub extern "C" fn sparse_matmul(
+    transa: *const c_char,
+    m: *const c_int,
+    n: *const c_int,
+    x: *const c_double,
+    y: *mut c_double,
+    dparm: *const c_double,
+    iparm: *mut c_int
+) {
+    unsafe {
+        let (m, n) = (*m as usize, *n as usize);
+        let indptr = slice::from_raw_parts(iparm, n + 1);
+        let nnz = indptr[n] as usize;
+        let indices = slice::from_raw_parts(iparm.offset(n as isize + 1), nnz);
+        let values = slice::from_raw_parts(dparm, nnz);
+
+        if *transa as u8 == b'n' || *transa as u8 == b'N' {
+            // y = A * x, where x has n elements and y has m elements
+            let x = slice::from_raw_parts(x, n);
+            let y = slice::from_raw_parts_mut(y, m);
+            for value in y.iter_mut() {
+                *value = 0f64;
+            }
+            for j in 0..n {
+                let xj = x[j];
+                for p in indptr[j] as usize..indptr[j + 1] as usize {
+                    y[indices[p] as usize] += values[p] * xj;
+                }
+            }
+        } else {
+            // y = A^T * x, where x has m elements and y has n elements
+            let x = slice::from_raw_parts(x, m);
+            let y = slice::from_raw_parts_mut(y, n);
+            for j in 0..n {
+                let mut sum = 0f64;
+                for p in indptr[j] as usize..indptr[j + 1] as usize {
+                    sum += values[p] * x[indices[p] as usize];
+                }
+                y[j] = sum;
+            }
+        }
+    }
+}
+
+// Selects which singular triplets to compute with DLANSVD_IRL
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Which {
+    Largest, // k largest singular values
+    Smallest // k smallest singular values
+}
+
+impl Which {
+    // Parse selector from character, 'L' for largest and 'S' for smallest (case-insensitive)
+    pub fn from_char(value: char) -> Self {
+        match Self::try_from_char(value) {
+            Ok(which) => which,
+            Err(err) => panic!("{}", err)
+        }
+    }
+
+    // Parse selector from character, returns error for unsupported character
+    pub fn try_from_char(value: char) -> MatrixResult<Self> {
+        match value {
+            'L' | 'l' => Ok(Which::Largest),
+            'S' | 's' => Ok(Which::Smallest),
+            other => Err(MatrixError::InvalidArgument(
+                format!("Invalid singular values selector '{}', expected 'L' or 'S'.", other)))
+        }
+    }
+
+    // Return character value that is passed to DLANSVD_IRL
+    pub fn as_char(&self) -> char {
+        match *self {
+            Which::Largest => 'L',
+            Which::Smallest => 'S'
+        }
+    }
+}
+
+// Options for Lanczos SVD based on DLANSVD_IRL, default values work well for most matrices.
+// Options can be chained, e.g. `LanczosOptions::new().tolerance(1e-10).max_restarts(10)`.
+#[derive(Clone, Debug)]
+pub struct LanczosOptions {
+    pub which: Which, // compute largest or smallest singular triplets
+    pub job: SvdJob, // singular vectors to compute
+    pub tolerance: f64, // desired relative accuracy of computed singular values
+    pub max_restarts: usize, // maximum number of restarts
+    pub dim: Option<usize>, // dimension of Krylov subspace, min(7 * k, min(rows, cols) + 1) if None
+    pub shifts: Option<usize>, // number of shifts per restart, dim - k if None
+    pub classical_gs: bool, // use classical instead of modified Gram-Schmidt reorthogonalization
+    pub extended_local_orth: bool // enforce extended local orthogonality among Lanczos vectors
+}
+
+impl LanczosOptions {
+    pub fn new() -> Self {
+        LanczosOptions {
+            which: Which::Largest,
+            job: SvdJob::Both,
+            tolerance: 1e-8,
+            // it looks like dlansvd_irl forces this number of iterations, current value works
+            // well enough, original value was 10
+            max_restarts: 3,
+            dim: None,
+            shifts: None,
+            classical_gs: false,
+            extended_local_orth: true
+        }
+    }
+
+    pub fn which(mut self, value: Which) -> Self {
+        self.which = value;
+        self
+    }
+
+    pub fn job(mut self, value: SvdJob) -> Self {
+        self.job = value;
+        self
+    }
+
+    pub fn tolerance(mut self, value: f64) -> Self {
+        self.tolerance = value;
+        self
+    }
+
+    pub fn max_restarts(mut self, value: usize) -> Self {
+        self.max_restarts = value;
+        self
+    }
+
+    pub fn dim(mut self, value: usize) -> Self {
+        self.dim = Some(value);
+        self
+    }
+
+    pub fn shifts(mut self, value: usize) -> Self {
+        self.shifts = Some(value);
+        self
+    }
+
+    pub fn classical_gs(mut self, value: bool) -> Self {
+        self.classical_gs = value;
+        self
+    }
+
+    pub fn extended_local_orth(mut self, value: bool) -> Self {
+        self.extended_local_orth = value;
+        self
+    }
+}
+
+impl Default for LanczosOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Linear operator A of shape (rows, cols) that is defined by matrix-vector products only, can be
+// used as matrix-free input for Lanczos SVD, e.g. for centered matrices or products of matrices.
+// Matrix-vector products are computed while PROPACK routine is running, so they must not run
+// Lanczos SVD themselves, such call returns error instead.
+pub trait LinearOperator {
+    // Return rows and columns of the operator as shape tuple
+    fn shape(&self) -> (usize, usize);
+
+    // Compute y = A * x, where x has `cols` elements and y has `rows` elements
+    fn matvec(&self, x: &[f64], y: &mut [f64]);
+
+    // Compute y = A^T * x, where x has `rows` elements and y has `cols` elements
+    fn rmatvec(&self, x: &[f64], y: &mut [f64]);
+}
+
+// Context that is passed as `dparm` to `operator_matmul`, keeps reference to the operator and
+// panic cause, if any, since we cannot unwind through Fortran code.
+pub struct OperatorContext<'a> {
+    op: &'a dyn LinearOperator,
+    cause: Option<Box<dyn Any + Send>>
+}
+
+impl<'a> OperatorContext<'a> {
+    pub fn new(op: &'a dyn LinearOperator) -> Self {
+        OperatorContext { op, cause: None }
+    }
+
+    // Return panic of the operator as error, if any, resetting context state
+    pub fn take_error(&mut self) -> Option<MatrixError> {
+        self.cause.take().map(|cause| {
+            let msg = if let Some(msg) = cause.downcast_ref::<&str>() {
+                msg.to_string()
+            } else if let Some(msg) = cause.downcast_ref::<String>() {
+                msg.clone()
+            } else {
+                "unknown cause".to_string()
+            };
+            MatrixError::InvalidArgument(format!("Linear operator failed: {}", msg))
+        })
+    }
+}
+
+// APROD trampoline for `LinearOperator`, recovers context from `dparm` pointer and dispatches
+// matrix-vector product to the operator. If operator panics, cause is stored in the context and
+// output is filled with zeros for this and all subsequent calls.
+#[no_mangle]
+pub extern "C" fn operator_matmul(
+    transa: *const c_char,
+    m: *const c_int,
+    n: *const c_int,
+    x: *const c_double,
+    y: *mut c_double,
+    dparm: *const c_double,
+    _iparm: *mut c_int
+) {
+    unsafe {
+        let context = &mut *(dparm as *mut OperatorContext);
+        let (m, n) = (*m as usize, *n as usize);
+        let transpose = !(*transa as u8 == b'n' || *transa as u8 == b'N');
+        let (xlen, ylen) = if transpose { (m, n) } else { (n, m) };
+        let x = slice::from_raw_parts(x, xlen);
+        let y = slice::from_raw_parts_mut(y, ylen);
+
+        if context.cause.is_none() {
+            let op = context.op;
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                if transpose { op.rmatvec(x, y) } else { op.matvec(x, y) }
+            }));
+            if let Err(cause) = res {
+                context.cause = Some(cause);
+            }
+        }
+
+        if context.cause.is_some() {
+            for value in y.iter_mut() {
+                *value = 0f64;
+            }
+        }
+    }
+}
+
+// Context that is passed as `dparm` to `counting_matmul`, keeps APROD function with its
+// parameters and number of matrix-vector products of a single Lanczos SVD.
+pub struct CountingContext {
+    aprod: PROPACK_EXTERN,
+    dparm: *const c_double,
+    iparm: *const c_int,
+    matvecs: usize
+}
+
+impl CountingContext {
+    pub fn new(aprod: PROPACK_EXTERN, dparm: *const c_double, iparm: *const c_int) -> Self {
+        CountingContext { aprod, dparm, iparm, matvecs: 0 }
+    }
+
+    // Return number of matrix-vector products computed so far
+    pub fn matvecs(&self) -> usize {
+        self.matvecs
+    }
+}
+
+// APROD trampoline that counts matrix-vector products, recovers context from `dparm` pointer and
+// dispatches product to the wrapped APROD function with its original parameters.
+#[no_mangle]
+pub extern "C" fn counting_matmul(
+    transa: *const c_char,
+    m: *const c_int,
+    n: *const c_int,
+    x: *const c_double,
+    y: *mut c_double,
+    dparm: *const c_double,
+    _iparm: *mut c_int
+) {
+    unsafe {
+        let context = &mut *(dparm as *mut CountingContext);
+        context.matvecs += 1;
+        if let Some(aprod) = context.aprod {
+            aprod(transa, m, n, x, y, context.dparm, context.iparm as *mut c_int);
+        }
+    }
+}
+
+// Leading integer counters of PROPACK statistics common block `timing`, see propack/stat.h
+#[repr(C)]
+struct Timing {
+    nopx: c_int, // number of matrix-vector products
+    nreorth: c_int,
+    ndot: c_int,
+    nreorthu: c_int,
+    nreorthv: c_int,
+    nitref: c_int,
+    nrestart: c_int, // number of restarts
+    nbsvd: c_int
+}
+
+// PROPACK routines update statistics in global common block `timing` on every call, calls are
+// serialized so that statistics are never read or written concurrently.
+static PROPACK_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    // Set while current thread runs PROPACK routine, APROD functions are called on the same
+    // thread, so nested call is detected instead of blocking on non-reentrant lock
+    static IN_PROPACK: Cell<bool> = const { Cell::new(false) };
+}
+
+// Run PROPACK routine while holding the lock, returns result of the routine and number of
+// restarts recorded by PROPACK during the call, or error if called from APROD function
+pub fn propack_call<T, F: FnOnce() -> T>(func: F) -> MatrixResult<(T, usize)> {
+    if IN_PROPACK.with(|flag| flag.replace(true)) {
+        return Err(MatrixError::InvalidArgument(
+            "Lanczos SVD cannot be called from APROD function or linear operator.".to_string()));
+    }
+    let res = {
+        // lock does not protect any Rust state, so poisoned lock can be used as is
+        let _guard = PROPACK_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let restarts = unsafe { ptr::read(ptr::addr_of!(timing_.nrestart)) };
+        let res = func();
+        let total_restarts = unsafe { ptr::read(ptr::addr_of!(timing_.nrestart)) };
+        (res, total_restarts.wrapping_sub(restarts) as usize)
+    };
+    IN_PROPACK.with(|flag| flag.set(false));
+    Ok(res)
+}
+
 extern "C" {
+    // PROPACK statistics, updated by Lanczos routines
+    static mut timing_: Timing;
+
     // from blas-sys, redefine so we don't need to import it
     fn dgemv_(
         trans: *const c_char,
@@ -66,7 +420,7 @@ extern "C" {
         jobv: *const c_char,
         m: *const c_int,
         n: *const c_int,
-        k: *const c_int,
+        k: *mut c_int,
         kmax: *const c_int,
         aprod: PROPACK_EXTERN,
         u: *mut c_double,
@@ -97,7 +451,7 @@ extern "C" {
         n: *const c_int,
         dim: *const c_int,
         p: *const c_int,
-        neig: *const c_int,
+        neig: *mut c_int,
         maxiter: *const c_int,
         aprod: PROPACK_EXTERN,
         u: *mut c_double,
@@ -125,8 +479,9 @@ pub unsafe fn dlansvd(
     jobv: u8,
     m: i32,
     n: i32,
-    k: i32,
+    k: &mut i32,
     kmax: i32,
+    aprod: PROPACK_EXTERN,
     u: &mut [f64],
     ldu: i32,
     sigma: &mut [f64],
@@ -141,17 +496,17 @@ pub unsafe fn dlansvd(
     doption: &[f64],
     ioption: &[i32],
     info: &mut i32,
-    dparm: &[f64],
-    iparm: &[i32]
+    dparm: *const f64,
+    iparm: *const i32
 ) {
     dlansvd_(
         &(jobu as c_char),
         &(jobv as c_char),
         &m,
         &n,
-        &k,
+        k,
         &kmax,
-        Some(dense_matmul),
+        aprod,
         u.as_mut_ptr(),
         &ldu,
         sigma.as_mut_ptr(),
@@ -166,8 +521,8 @@ pub unsafe fn dlansvd(
         doption.as_ptr(),
         ioption.as_ptr(),
         info,
-        dparm.as_ptr(),
-        iparm.as_ptr()
+        dparm,
+        iparm
     )
 }
 
@@ -180,8 +535,9 @@ pub unsafe fn dlansvd_irl(
     n: i32,
     dim: i32,
     p: i32,
-    neig: i32,
+    neig: &mut i32,
     maxiter: i32,
+    aprod: PROPACK_EXTERN,
     u: &mut [f64],
     ldu: i32,
     sigma: &mut [f64],
@@ -196,8 +552,8 @@ pub unsafe fn dlansvd_irl(
     doption: &[f64],
     ioption: &[i32],
     info: &mut i32,
-    dparm: &[f64],
-    iparm: &[i32]
+    dparm: *const f64,
+    iparm: *const i32
 ) {
     dlansvd_irl_(
         &(which as c_char),
@@ -207,9 +563,9 @@ pub unsafe fn dlansvd_irl(
         &n,
         &dim,
         &p,
-        &neig,
+        neig,
         &maxiter,
-        Some(dense_matmul),
+        aprod,
         u.as_mut_ptr(),
         &ldu,
         sigma.as_mut_ptr(),
@@ -224,7 +580,7 @@ pub unsafe fn dlansvd_irl(
         doption.as_ptr(),
         ioption.as_ptr(),
         info,
-        dparm.as_ptr(),
-        iparm.as_ptr()
+        dparm,
+        iparm
     );
 }
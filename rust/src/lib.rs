@@ -25,20 +25,38 @@ extern crate openblas_src;
 extern crate rand;
 
 pub mod lanczos;
+#[macro_use]
 pub mod internal;
+pub mod sparse;
 
+use std::cmp;
 use std::ffi::CString;
-use std::mem;
 use std::panic;
 use std::ptr;
-use libc::{int32_t, c_double, c_char, size_t};
-use internal::{DoubleMatrix, SVD};
+use std::slice;
+use libc::{c_double, c_char, size_t};
+use internal::{ComplexMatrix, DoubleMatrix, Eigen, EigenRange, GeneralEigen, LanczosSvd};
+use internal::{LeastSquares, MatrixError, MatrixResult, QR, SVD, SvdJob, SvdRange};
+use lanczos::{LanczosOptions, Which};
+
+// Error kinds reported in `err_kind` field of result structs, `err_info` contains info code of
+// LAPACK or PROPACK routine for ERR_NOT_CONVERGED and ERR_SINGULAR, and 0 otherwise.
+pub const ERR_NONE: i32 = 0; // operation succeeded
+pub const ERR_PANIC: i32 = 1; // unexpected failure, e.g. panic in library code
+pub const ERR_SHAPE_MISMATCH: i32 = 2;
+pub const ERR_INDEX_OUT_OF_BOUNDS: i32 = 3;
+pub const ERR_NOT_CONVERGED: i32 = 4;
+pub const ERR_SINGULAR: i32 = 5;
+pub const ERR_INVALID_ARGUMENT: i32 = 6;
+pub const ERR_ALLOCATION_FAILURE: i32 = 7;
 
 // PtrResult to capture and return either valid pointer to a matrix or error message.
 // Only one pointer should be set.
 #[repr(C)]
 pub struct PtrResult {
     ptr: *const DoubleMatrix,
+    err_kind: i32,
+    err_info: i32,
     err: *const c_char
 }
 
@@ -46,14 +64,48 @@ pub struct PtrResult {
 // err pointer should be set to NULL.
 #[repr(C)]
 pub struct VoidResult {
+    err_kind: i32,
+    err_info: i32,
     err: *const c_char
 }
 
-// DoubleArray struct represents C array with length included.
+// DoubleArray struct represents C array with length included, array is borrowed from the matrix
+// and is valid until matrix is modified or released.
 #[repr(C)]
 pub struct DoubleArray {
-    len: int32_t,
-    data: *const c_double
+    len: i32,
+    data: *const c_double,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// ScalarResult to return either scalar value of the matrix, e.g. sum or norm, or error message.
+#[repr(C)]
+pub struct ScalarResult {
+    value: c_double,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// IntResult to return either integer value, e.g. number of rows or boolean flag, or error message.
+#[repr(C)]
+pub struct IntResult {
+    value: i32,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// StringResult to return either C string that must be released with `string_dealloc` or error
+// message.
+#[repr(C)]
+pub struct StringResult {
+    ptr: *const c_char,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
 }
 
 // SvdResult to capture and return either set of valid matrix pointers or error message.
@@ -63,12 +115,111 @@ pub struct SvdResult {
     u: *const DoubleMatrix,
     s: *const DoubleMatrix,
     v: *const DoubleMatrix,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// EigenResult to capture and return eigenvalues and eigenvectors or error message.
+#[repr(C)]
+pub struct EigenResult {
+    values: *const DoubleMatrix,
+    vectors: *const DoubleMatrix,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// GeneralEigenResult to capture and return eigenvalues and eigenvectors of general matrix or error
+// message. Complex values are returned as pairs of real and imaginary parts, pointers to left or
+// right eigenvectors are set to NULL when they were not computed.
+#[repr(C)]
+pub struct GeneralEigenResult {
+    values_re: *const DoubleMatrix,
+    values_im: *const DoubleMatrix,
+    left_re: *const DoubleMatrix,
+    left_im: *const DoubleMatrix,
+    right_re: *const DoubleMatrix,
+    right_im: *const DoubleMatrix,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// LanczosResult to capture result of Lanczos SVD with convergence diagnostics or error message.
+// `info` is 0 when all singular triplets converged, -1 when fewer than k triplets converged
+// within max number of restarts, and J > 0 when an invariant subspace of dimension J was found.
+// Pointers to u and v are set to NULL when singular vectors were not computed.
+#[repr(C)]
+pub struct LanczosResult {
+    u: *const DoubleMatrix,
+    s: *const DoubleMatrix,
+    v: *const DoubleMatrix,
+    bnd: *const DoubleMatrix,
+    info: i32,
+    restarts: i32,
+    matvecs: i32,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// QrResult to capture and return QR factorization or error message. Pointer to p is set to NULL
+// when column pivoting is not used.
+#[repr(C)]
+pub struct QrResult {
+    q: *const DoubleMatrix,
+    r: *const DoubleMatrix,
+    p: *const DoubleMatrix,
+    err_kind: i32,
+    err_info: i32,
+    err: *const c_char
+}
+
+// LstsqResult to capture and return least squares solution or error message. Pointer to s is set
+// to NULL when singular values are not computed.
+#[repr(C)]
+pub struct LstsqResult {
+    x: *const DoubleMatrix,
+    residuals: *const DoubleMatrix,
+    s: *const DoubleMatrix,
+    rank: i32,
+    err_kind: i32,
+    err_info: i32,
     err: *const c_char
 }
 
-// Convert error/panic cause into C string
+// Build Lanczos options from C arguments, `dim` and `shifts` are set to default values when
+// non-positive, flags are enabled when equal to 1
 #[inline]
-fn err_to_cstr(cause: Box<std::any::Any>) -> *const c_char {
+fn lanczos_options(
+    which: c_char,
+    tolerance: c_double,
+    max_restarts: i32,
+    dim: i32,
+    shifts: i32,
+    classical_gs: i32,
+    extended_local_orth: i32
+) -> MatrixResult<LanczosOptions>
+{
+    let mut options = LanczosOptions::new()
+        .which(Which::try_from_char(which as u8 as char)?)
+        .tolerance(tolerance)
+        .max_restarts(cmp::max(0, max_restarts) as usize)
+        .classical_gs(classical_gs == 1)
+        .extended_local_orth(extended_local_orth == 1);
+    if dim > 0 {
+        options = options.dim(dim as usize);
+    }
+    if shifts > 0 {
+        options = options.shifts(shifts as usize);
+    }
+    Ok(options)
+}
+
+// Convert error/panic cause into C string, string must be released with `string_dealloc`
+#[inline]
+fn err_to_cstr(cause: Box<dyn std::any::Any>) -> *const c_char {
     let err_msg = if cause.is::<String>() {
         format!("{}", *(*cause).downcast_ref::<String>().unwrap())
     } else if cause.is::<&str>() {
@@ -76,137 +227,596 @@ fn err_to_cstr(cause: Box<std::any::Any>) -> *const c_char {
     } else {
         format!("Unknown cause")
     };
-    let cstr = CString::new(err_msg).unwrap();
-    let cstr_ptr = cstr.as_ptr();
-    mem::forget(cstr);
-    cstr_ptr
+    to_cstr(err_msg)
 }
 
-// Function to catch panic and return ptr result for matrix
+// Convert string into C string, string must be released with `string_dealloc`
+#[inline]
+fn to_cstr(value: String) -> *const c_char {
+    // interior NUL bytes cannot be represented in C string and are removed
+    let value = if value.contains('\0') { value.replace('\0', "") } else { value };
+    CString::new(value).unwrap().into_raw()
+}
+
+// Return error if pointer is NULL, `name` is used in error message
+#[inline]
+fn ensure_not_null<T>(ptr: *const T, name: &str) -> MatrixResult<()> {
+    if ptr.is_null() {
+        Err(MatrixError::InvalidArgument(format!("Invalid NULL pointer for {}.", name)))
+    } else {
+        Ok(())
+    }
+}
+
+// Convert dimensions of the matrix, returns error if any dimension is negative
+#[inline]
+fn matrix_dims(rows: i32, cols: i32) -> MatrixResult<(usize, usize)> {
+    if rows < 0 || cols < 0 {
+        Err(MatrixError::InvalidArgument(format!("Invalid dimensions: {} x {}.", rows, cols)))
+    } else {
+        Ok((rows as usize, cols as usize))
+    }
+}
+
+// Dereference matrix pointer, returns error if pointer is NULL
+#[inline]
+fn matrix_ref<'a>(ptr: *const DoubleMatrix) -> MatrixResult<&'a DoubleMatrix> {
+    ensure_not_null(ptr, "matrix")?;
+    Ok(unsafe { &(*ptr) })
+}
+
+// Dereference mutable matrix pointer, returns error if pointer is NULL
 #[inline]
-fn try_catch_ptr<F: FnOnce() -> DoubleMatrix + panic::UnwindSafe>(func: F) -> PtrResult {
+fn matrix_mut<'a>(ptr: *mut DoubleMatrix) -> MatrixResult<&'a mut DoubleMatrix> {
+    ensure_not_null(ptr, "matrix")?;
+    Ok(unsafe { &mut (*ptr) })
+}
+
+// Return error if output matrix is the same as input matrix, i.e. would be modified while read
+#[inline]
+fn ensure_distinct(out: *mut DoubleMatrix, input: *const DoubleMatrix) -> MatrixResult<()> {
+    if ptr::eq(out, input) {
+        Err(MatrixError::InvalidArgument("Output matrix must not be an input matrix.".to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+// Run in-place operation on matrix `ptr` with input matrix `xptr`, input is copied when it is
+// the same matrix, so that mutable and shared references to the matrix never alias
+#[inline]
+fn in_place<F>(ptr: *mut DoubleMatrix, xptr: *const DoubleMatrix, func: F) -> MatrixResult<()>
+    where F: FnOnce(&mut DoubleMatrix, &DoubleMatrix) -> MatrixResult<()>
+{
+    if ptr::eq(ptr, xptr) {
+        let x = matrix_ref(xptr)?.clone();
+        func(matrix_mut(ptr)?, &x)
+    } else {
+        let x = matrix_ref(xptr)?;
+        func(matrix_mut(ptr)?, x)
+    }
+}
+
+// Data of the matrix, returns error if length does not fit into C int
+#[inline]
+fn data_slice(matrix: &DoubleMatrix) -> MatrixResult<&[f64]> {
+    let data = matrix.data();
+    if data.len() > i32::MAX as usize {
+        return Err(MatrixError::AllocationFailure(
+            format!("Matrix is too large to copy into array: {} elements.", data.len())));
+    }
+    Ok(data)
+}
+
+// Convert array of `len` values into slice, array can be NULL only when it is empty
+#[inline]
+unsafe fn buffer_to_slice<'a>(ptr: *const c_double, len: size_t) -> MatrixResult<&'a [f64]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    ensure_not_null(ptr, "array")?;
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+// Error fields of result struct: error kind, info code and error message
+struct ErrorFields {
+    kind: i32,
+    info: i32,
+    err: *const c_char
+}
+
+impl ErrorFields {
+    // Error fields for matrix error, see ERR_* constants
+    fn from_error(err: MatrixError) -> Self {
+        let kind = match err {
+            MatrixError::ShapeMismatch(_) => ERR_SHAPE_MISMATCH,
+            MatrixError::IndexOutOfBounds(_) => ERR_INDEX_OUT_OF_BOUNDS,
+            MatrixError::NotConverged(_, _) => ERR_NOT_CONVERGED,
+            MatrixError::Singular(_, _) => ERR_SINGULAR,
+            MatrixError::InvalidArgument(_) => ERR_INVALID_ARGUMENT,
+            MatrixError::AllocationFailure(_) => ERR_ALLOCATION_FAILURE
+        };
+        let info = err.info().unwrap_or(0);
+        ErrorFields { kind, info, err: to_cstr(err.to_string()) }
+    }
+
+    // Error fields for panic cause
+    fn from_panic(cause: Box<dyn std::any::Any>) -> Self {
+        ErrorFields { kind: ERR_PANIC, info: 0, err: err_to_cstr(cause) }
+    }
+}
+
+// Function to run operation that reports errors as Result and catch panic, both are converted
+// into error fields of result struct
+#[inline]
+fn try_catch<T, F>(func: F) -> Result<T, ErrorFields>
+    where F: FnOnce() -> MatrixResult<T> + panic::UnwindSafe
+{
     match panic::catch_unwind(func) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(ErrorFields::from_error(err)),
+        Err(cause) => Err(ErrorFields::from_panic(cause))
+    }
+}
+
+// Function to catch panic and return ptr result for matrix
+#[inline]
+fn try_catch_ptr<F>(func: F) -> PtrResult
+    where F: FnOnce() -> MatrixResult<DoubleMatrix> + panic::UnwindSafe
+{
+    match try_catch(func) {
         Ok(matrix) => {
             let matrix = Box::new(matrix);
-            PtrResult { ptr: Box::into_raw(matrix), err: ptr::null() }
+            PtrResult {
+                ptr: Box::into_raw(matrix),
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            PtrResult {
+                ptr: ptr::null(),
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Function to catch panic and return scalar result
+#[inline]
+fn try_catch_scalar<F>(func: F) -> ScalarResult
+    where F: FnOnce() -> MatrixResult<f64> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(value) => {
+            ScalarResult { value, err_kind: ERR_NONE, err_info: 0, err: ptr::null() }
         },
-        Err(cause) => {
-            PtrResult { ptr: ptr::null(), err: err_to_cstr(cause) }
+        Err(fields) => {
+            ScalarResult {
+                value: 0f64,
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Function to catch panic and return integer result
+#[inline]
+fn try_catch_int<F>(func: F) -> IntResult
+    where F: FnOnce() -> MatrixResult<i32> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(value) => IntResult { value, err_kind: ERR_NONE, err_info: 0, err: ptr::null() },
+        Err(fields) => {
+            IntResult { value: 0, err_kind: fields.kind, err_info: fields.info, err: fields.err }
+        }
+    }
+}
+
+// Function to catch panic and return string result, string must be released with
+// `string_dealloc`
+#[inline]
+fn try_catch_string<F>(func: F) -> StringResult
+    where F: FnOnce() -> MatrixResult<String> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(value) => {
+            StringResult {
+                ptr: to_cstr(value),
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            StringResult {
+                ptr: ptr::null(),
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
         }
     }
 }
 
 // Function to catch panic and return void result
 #[inline]
-fn try_catch_void<F: FnOnce() -> () + panic::UnwindSafe>(func: F) -> VoidResult {
-    match panic::catch_unwind(func) {
-        Ok(_) => VoidResult { err: ptr::null() },
-        Err(cause) => VoidResult { err: err_to_cstr(cause) }
+fn try_catch_void<F>(func: F) -> VoidResult
+    where F: FnOnce() -> MatrixResult<()> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(_) => VoidResult { err_kind: ERR_NONE, err_info: 0, err: ptr::null() },
+        Err(fields) => VoidResult { err_kind: fields.kind, err_info: fields.info, err: fields.err }
+    }
+}
+
+// Convert optional matrix into raw pointer, NULL when matrix is not set
+#[inline]
+fn option_to_ptr(matrix: Option<DoubleMatrix>) -> *const DoubleMatrix {
+    match matrix {
+        Some(matrix) => Box::into_raw(Box::new(matrix)),
+        None => ptr::null()
     }
 }
 
 // Function to catch panic and return svd result
 #[inline]
-fn try_catch_svd<F: FnOnce() -> SVD + panic::UnwindSafe>(func: F) -> SvdResult {
-    match panic::catch_unwind(func) {
+fn try_catch_svd<F: FnOnce() -> MatrixResult<SVD> + panic::UnwindSafe>(func: F) -> SvdResult {
+    match try_catch(func) {
         Ok(svd) => {
             // convert matrices into raw pointers
-            let u_ptr = match svd.u {
-                Some(matrix) => Box::into_raw(Box::new(matrix)),
-                None => ptr::null()
-            };
-            let s_ptr = Box::into_raw(Box::new(svd.s));
-            let v_ptr = match svd.v {
-                Some(matrix) => Box::into_raw(Box::new(matrix)),
-                None => ptr::null()
-            };
-
             SvdResult {
-                u: u_ptr,
-                s: s_ptr,
-                v: v_ptr,
+                u: option_to_ptr(svd.u),
+                s: Box::into_raw(Box::new(svd.s)),
+                v: option_to_ptr(svd.v),
+                err_kind: ERR_NONE,
+                err_info: 0,
                 err: ptr::null()
             }
         },
-        Err(cause) => {
+        Err(fields) => {
             SvdResult {
                 u: ptr::null(),
                 s: ptr::null(),
                 v: ptr::null(),
-                err: err_to_cstr(cause)
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Function to catch panic and return eigen result
+#[inline]
+fn try_catch_eigen<F: FnOnce() -> MatrixResult<Eigen> + panic::UnwindSafe>(func: F) -> EigenResult {
+    match try_catch(func) {
+        Ok(eigen) => {
+            EigenResult {
+                values: Box::into_raw(Box::new(eigen.values)),
+                vectors: Box::into_raw(Box::new(eigen.vectors)),
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            EigenResult {
+                values: ptr::null(),
+                vectors: ptr::null(),
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Convert optional complex matrix into pair of raw pointers
+#[inline]
+fn complex_to_ptr(matrix: Option<ComplexMatrix>) -> (*const DoubleMatrix, *const DoubleMatrix) {
+    match matrix {
+        Some(matrix) => {
+            (Box::into_raw(Box::new(matrix.re)) as *const DoubleMatrix,
+                Box::into_raw(Box::new(matrix.im)) as *const DoubleMatrix)
+        },
+        None => (ptr::null(), ptr::null())
+    }
+}
+
+// Function to catch panic and return general eigen result
+#[inline]
+fn try_catch_general_eigen<F>(func: F) -> GeneralEigenResult
+    where F: FnOnce() -> MatrixResult<GeneralEigen> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(eig) => {
+            let (values_re, values_im) = complex_to_ptr(Some(eig.values));
+            let (left_re, left_im) = complex_to_ptr(eig.left);
+            let (right_re, right_im) = complex_to_ptr(eig.right);
+
+            GeneralEigenResult {
+                values_re,
+                values_im,
+                left_re,
+                left_im,
+                right_re,
+                right_im,
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            GeneralEigenResult {
+                values_re: ptr::null(),
+                values_im: ptr::null(),
+                left_re: ptr::null(),
+                left_im: ptr::null(),
+                right_re: ptr::null(),
+                right_im: ptr::null(),
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Function to catch panic and return lanczos result
+#[inline]
+fn try_catch_lanczos<F>(func: F) -> LanczosResult
+    where F: FnOnce() -> MatrixResult<LanczosSvd> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(res) => {
+            let status = res.status;
+            let svd = res.svd;
+
+            LanczosResult {
+                u: option_to_ptr(svd.u),
+                s: Box::into_raw(Box::new(svd.s)),
+                v: option_to_ptr(svd.v),
+                bnd: Box::into_raw(Box::new(res.bnd)),
+                info: status.info(),
+                restarts: res.restarts as i32,
+                matvecs: res.matvecs as i32,
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            LanczosResult {
+                u: ptr::null(),
+                s: ptr::null(),
+                v: ptr::null(),
+                bnd: ptr::null(),
+                info: 0,
+                restarts: 0,
+                matvecs: 0,
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
             }
         }
     }
 }
 
+// Function to catch panic and return qr result
+#[inline]
+fn try_catch_qr<F: FnOnce() -> MatrixResult<QR> + panic::UnwindSafe>(func: F) -> QrResult {
+    match try_catch(func) {
+        Ok(qr) => {
+            QrResult {
+                q: Box::into_raw(Box::new(qr.q)),
+                r: Box::into_raw(Box::new(qr.r)),
+                p: option_to_ptr(qr.p),
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            QrResult {
+                q: ptr::null(),
+                r: ptr::null(),
+                p: ptr::null(),
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Function to catch panic and return least squares result
+#[inline]
+fn try_catch_lstsq<F>(func: F) -> LstsqResult
+    where F: FnOnce() -> MatrixResult<LeastSquares> + panic::UnwindSafe
+{
+    match try_catch(func) {
+        Ok(ls) => {
+            LstsqResult {
+                x: Box::into_raw(Box::new(ls.x)),
+                residuals: Box::into_raw(Box::new(ls.residuals)),
+                s: option_to_ptr(ls.s),
+                rank: ls.rank as i32,
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            LstsqResult {
+                x: ptr::null(),
+                residuals: ptr::null(),
+                s: ptr::null(),
+                rank: 0,
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
+}
+
+// Create matrix from array of `len` values in column-major order, values are copied, so the
+// caller keeps ownership of the array
 #[no_mangle]
 pub extern "C" fn alloc_from_array(
-    rows: int32_t,
-    cols: int32_t,
+    rows: i32,
+    cols: i32,
+    len: size_t,
+    ptr: *const c_double
+) -> PtrResult
+{
+    try_catch_ptr(|| {
+        let (rows, cols) = matrix_dims(rows, cols)?;
+        let vec = unsafe { buffer_to_slice(ptr, len)? }.to_vec();
+        DoubleMatrix::try_new(rows, cols, vec)
+    })
+}
+
+// Allocate zero-filled buffer of `len` values that can be adopted by `alloc_from_buffer`,
+// returns NULL if buffer cannot be allocated; buffer that was not adopted must be released
+// with `buffer_dealloc`
+#[no_mangle]
+pub extern "C" fn alloc_buffer(len: size_t) -> *mut c_double {
+    match DoubleMatrix::try_alloc(len, 1, 0f64) {
+        Ok(data) => Box::into_raw(data.into_boxed_slice()) as *mut c_double,
+        Err(_) => ptr::null_mut()
+    }
+}
+
+// Release buffer allocated by `alloc_buffer`, `len` must match length of the buffer
+#[no_mangle]
+pub extern "C" fn buffer_dealloc(ptr: *mut c_double, len: size_t) {
+    if !ptr.is_null() {
+        unsafe { drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len))); }
+    }
+}
+
+// Create matrix that takes ownership of the buffer without copying, buffer must be allocated by
+// `alloc_buffer` with the same `len`; buffer is released when matrix cannot be created and must
+// not be used after this call
+#[no_mangle]
+pub extern "C" fn alloc_from_buffer(
+    rows: i32,
+    cols: i32,
     len: size_t,
     ptr: *mut c_double
 ) -> PtrResult
 {
-    let vec = unsafe { Vec::from_raw_parts(ptr, len, len) };
-    try_catch_ptr(|| DoubleMatrix::new(rows as usize, cols as usize, vec))
+    try_catch_ptr(|| {
+        ensure_not_null(ptr, "buffer")?;
+        let vec = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)) }.into_vec();
+        // buffer is adopted first, so it is released when dimensions are invalid
+        let (rows, cols) = matrix_dims(rows, cols)?;
+        DoubleMatrix::try_new(rows, cols, vec)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn alloc_rand(rows: int32_t, cols: int32_t) -> PtrResult {
-    try_catch_ptr(|| DoubleMatrix::new_random(rows as usize, cols as usize))
+pub extern "C" fn alloc_rand(rows: i32, cols: i32) -> PtrResult {
+    try_catch_ptr(|| {
+        let (rows, cols) = matrix_dims(rows, cols)?;
+        DoubleMatrix::try_new_random(rows, cols)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn alloc_zeros(rows: int32_t, cols: int32_t) -> PtrResult {
-    try_catch_ptr(|| DoubleMatrix::zeros(rows as usize, cols as usize))
+pub extern "C" fn alloc_zeros(rows: i32, cols: i32) -> PtrResult {
+    try_catch_ptr(|| {
+        let (rows, cols) = matrix_dims(rows, cols)?;
+        DoubleMatrix::try_zeros(rows, cols)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn alloc_ones(rows: int32_t, cols: int32_t) -> PtrResult {
-    try_catch_ptr(|| DoubleMatrix::ones(rows as usize, cols as usize))
+pub extern "C" fn alloc_ones(rows: i32, cols: i32) -> PtrResult {
+    try_catch_ptr(|| {
+        let (rows, cols) = matrix_dims(rows, cols)?;
+        DoubleMatrix::try_ones(rows, cols)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn alloc_identity(rows: int32_t, cols: int32_t) -> PtrResult {
-    try_catch_ptr(|| DoubleMatrix::identity(rows as usize, cols as usize))
+pub extern "C" fn alloc_identity(rows: i32, cols: i32) -> PtrResult {
+    try_catch_ptr(|| {
+        let (rows, cols) = matrix_dims(rows, cols)?;
+        DoubleMatrix::try_identity(rows, cols)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_rows(ptr: *const DoubleMatrix) -> int32_t {
-    unsafe { (*ptr).rows() as int32_t }
+pub extern "C" fn matrix_rows(ptr: *const DoubleMatrix) -> IntResult {
+    try_catch_int(|| Ok(matrix_ref(ptr)?.rows() as i32))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_cols(ptr: *const DoubleMatrix) -> int32_t {
-    unsafe { (*ptr).cols() as int32_t }
+pub extern "C" fn matrix_cols(ptr: *const DoubleMatrix) -> IntResult {
+    try_catch_int(|| Ok(matrix_ref(ptr)?.cols() as i32))
 }
 
 #[no_mangle]
 pub extern "C" fn matrix_data_array(ptr: *const DoubleMatrix) -> DoubleArray {
-    // TODO: Add check for length at most being i32 max value, currently check exists in Java
-    let arr = unsafe { (*ptr).data() };
-    DoubleArray { len: arr.len() as i32, data: arr.as_ptr() }
+    match try_catch(|| data_slice(matrix_ref(ptr)?)) {
+        Ok(arr) => {
+            DoubleArray {
+                len: arr.len() as i32,
+                data: arr.as_ptr(),
+                err_kind: ERR_NONE,
+                err_info: 0,
+                err: ptr::null()
+            }
+        },
+        Err(fields) => {
+            DoubleArray {
+                len: 0,
+                data: ptr::null(),
+                err_kind: fields.kind,
+                err_info: fields.info,
+                err: fields.err
+            }
+        }
+    }
 }
 
+// Pretty string of the matrix, string must be released with `string_dealloc`
 #[no_mangle]
 pub extern "C" fn matrix_pretty_string(
     ptr: *const DoubleMatrix,
-    truncate: int32_t
-) -> *const c_char
+    truncate: i32
+) -> StringResult
 {
-    // If truncated, show up to a certain precision
-    let matrix_str = unsafe {
-        if truncate == 1 { format!("{:.1$}", *ptr, 3) } else { format!("{}", *ptr) }
-    };
-    let cstr = CString::new(matrix_str).unwrap();
-    let ptr = cstr.as_ptr();
-    mem::forget(cstr);
-    ptr
+    try_catch_string(|| {
+        let this = matrix_ref(ptr)?;
+        // If truncated, show up to a certain precision
+        if truncate == 1 { Ok(format!("{:.1$}", this, 3)) } else { Ok(format!("{}", this)) }
+    })
 }
 
+// Release string returned by the library, e.g. error message or `matrix_pretty_string`
 #[no_mangle]
-pub extern "C" fn matrix_dealloc(ptr: *mut DoubleMatrix) {
-    unsafe { drop(Box::from_raw(ptr)); }
+pub extern "C" fn string_dealloc(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        unsafe { drop(CString::from_raw(ptr)); }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_dealloc(ptr: *mut DoubleMatrix) -> VoidResult {
+    try_catch_void(|| {
+        ensure_not_null(ptr, "matrix")?;
+        unsafe { drop(Box::from_raw(ptr)); }
+        Ok(())
+    })
 }
 
 #[no_mangle]
@@ -215,7 +825,7 @@ pub extern "C" fn matrix_add_scalar(
     scalar: c_double
 ) -> PtrResult
 {
-    try_catch_ptr(|| unsafe { (*ptr).add_scalar(scalar) })
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.add_scalar(scalar)))
 }
 
 #[no_mangle]
@@ -224,9 +834,11 @@ pub extern "C" fn matrix_add_matrix(
     aptr: *const DoubleMatrix
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    let that = unsafe { &(*aptr) };
-    try_catch_ptr(|| this.add_matrix(that))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_add_matrix(that)
+    })
 }
 
 #[no_mangle]
@@ -235,7 +847,10 @@ pub extern "C" fn matrix_add_in_place_scalar(
     scalar: c_double
 ) -> VoidResult
 {
-    try_catch_void(|| unsafe { (*ptr).add_scalar_mut(scalar); })
+    try_catch_void(|| {
+        matrix_mut(ptr)?.add_scalar_mut(scalar);
+        Ok(())
+    })
 }
 
 #[no_mangle]
@@ -244,11 +859,7 @@ pub extern "C" fn matrix_add_in_place_matrix(
     aptr: *const DoubleMatrix
 ) -> VoidResult
 {
-    try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.add_matrix_mut(that);
-    })
+    try_catch_void(|| in_place(ptr, aptr, |this, that| this.try_axpy(1f64, that)))
 }
 
 #[no_mangle]
@@ -257,7 +868,7 @@ pub extern "C" fn matrix_sub_scalar(
     scalar: c_double
 ) -> PtrResult
 {
-    try_catch_ptr(|| unsafe { (*ptr).sub_scalar(scalar) })
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.sub_scalar(scalar)))
 }
 
 #[no_mangle]
@@ -266,9 +877,11 @@ pub extern "C" fn matrix_sub_matrix(
     aptr: *const DoubleMatrix
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    let that = unsafe { &(*aptr) };
-    try_catch_ptr(|| this.sub_matrix(that))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_sub_matrix(that)
+    })
 }
 
 #[no_mangle]
@@ -278,7 +891,10 @@ pub extern "C" fn matrix_sub_in_place_scalar(
 ) -> VoidResult
 {
     // TODO: check that negation is correct for scalar
-    try_catch_void(|| unsafe { (*ptr).sub_scalar_mut(scalar); })
+    try_catch_void(|| {
+        matrix_mut(ptr)?.sub_scalar_mut(scalar);
+        Ok(())
+    })
 }
 
 #[no_mangle]
@@ -287,11 +903,7 @@ pub extern "C" fn matrix_sub_in_place_matrix(
     aptr: *const DoubleMatrix
 ) -> VoidResult
 {
-    try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.sub_matrix_mut(that);
-    })
+    try_catch_void(|| in_place(ptr, aptr, |this, that| this.try_axpy(-1f64, that)))
 }
 
 #[no_mangle]
@@ -300,8 +912,10 @@ pub extern "C" fn matrix_mul_scalar(
     scalar: c_double
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    try_catch_ptr(|| this.mul_scalar(scalar))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        Ok(this.mul_scalar(scalar))
+    })
 }
 
 #[no_mangle]
@@ -310,9 +924,11 @@ pub extern "C" fn matrix_mul_matrix(
     aptr: *const DoubleMatrix
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    let that = unsafe { &(*aptr) };
-    try_catch_ptr(|| this.mul_matrix(that))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_mul_matrix(that)
+    })
 }
 
 #[no_mangle]
@@ -321,7 +937,10 @@ pub extern "C" fn matrix_mul_in_place_scalar(
     scalar: c_double
 ) -> VoidResult
 {
-    try_catch_void(|| unsafe { (*ptr).mul_scalar_mut(scalar); })
+    try_catch_void(|| {
+        matrix_mut(ptr)?.mul_scalar_mut(scalar);
+        Ok(())
+    })
 }
 
 #[no_mangle]
@@ -330,11 +949,7 @@ pub extern "C" fn matrix_mul_in_place_matrix(
     aptr: *const DoubleMatrix
 ) -> VoidResult
 {
-    try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.mul_matrix_mut(that);
-    })
+    try_catch_void(|| in_place(ptr, aptr, |this, that| this.try_mul_matrix_mut(that)))
 }
 
 #[no_mangle]
@@ -343,8 +958,10 @@ pub extern "C" fn matrix_div_scalar(
     scalar: c_double
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    try_catch_ptr(|| this.div_scalar(scalar))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        Ok(this.div_scalar(scalar))
+    })
 }
 
 #[no_mangle]
@@ -353,9 +970,11 @@ pub extern "C" fn matrix_div_matrix(
     aptr: *const DoubleMatrix
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    let that = unsafe { &(*aptr) };
-    try_catch_ptr(|| this.div_matrix(that))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_div_matrix(that)
+    })
 }
 
 #[no_mangle]
@@ -364,7 +983,10 @@ pub extern "C" fn matrix_div_in_place_scalar(
     scalar: c_double
 ) -> VoidResult
 {
-    try_catch_void(|| unsafe { (*ptr).div_scalar_mut(scalar); })
+    try_catch_void(|| {
+        matrix_mut(ptr)?.div_scalar_mut(scalar);
+        Ok(())
+    })
 }
 
 #[no_mangle]
@@ -373,11 +995,7 @@ pub extern "C" fn matrix_div_in_place_matrix(
     aptr: *const DoubleMatrix
 ) -> VoidResult
 {
-    try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.div_matrix_mut(that);
-    })
+    try_catch_void(|| in_place(ptr, aptr, |this, that| this.try_div_matrix_mut(that)))
 }
 
 #[no_mangle]
@@ -386,9 +1004,11 @@ pub extern "C" fn matrix_mmul_matrix(
     aptr: *const DoubleMatrix
 ) -> PtrResult
 {
-    let this = unsafe { &(*ptr) };
-    let that = unsafe { &(*aptr) };
-    try_catch_ptr(|| this.mmul(that))
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_mmul(that)
+    })
 }
 
 #[no_mangle]
@@ -396,192 +1016,655 @@ pub extern "C" fn matrix_mmul_in_place_matrix(
     ptr: *mut DoubleMatrix,
     aptr: *const DoubleMatrix
 ) -> VoidResult
+{
+    try_catch_void(|| in_place(ptr, aptr, |this, that| {
+        *this = this.try_mmul(that)?;
+        Ok(())
+    }))
+}
+
+// Matrix multiply op(A) * op(B), operands are transposed when corresponding flag is equal to 1
+#[no_mangle]
+pub extern "C" fn matrix_mmul_transpose(
+    ptr: *const DoubleMatrix,
+    aptr: *const DoubleMatrix,
+    transa: i32,
+    transb: i32
+) -> PtrResult
+{
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_mmul_transpose(that, transa == 1, transb == 1)
+    })
+}
+
+// General matrix multiply C = alpha * op(A) * op(B) + beta * C in place of C, operands are
+// transposed when corresponding flag is equal to 1; C must not be the same matrix as A or B
+#[no_mangle]
+pub extern "C" fn matrix_gemm(
+    transa: i32,
+    transb: i32,
+    alpha: c_double,
+    aptr: *const DoubleMatrix,
+    bptr: *const DoubleMatrix,
+    beta: c_double,
+    cptr: *mut DoubleMatrix
+) -> VoidResult
+{
+    try_catch_void(|| {
+        let a = matrix_ref(aptr)?;
+        let b = matrix_ref(bptr)?;
+        ensure_distinct(cptr, aptr)?;
+        ensure_distinct(cptr, bptr)?;
+        let c = matrix_mut(cptr)?;
+        DoubleMatrix::try_gemm(transa == 1, transb == 1, alpha, a, b, beta, c)
+    })
+}
+
+// Gram matrix A^T * A
+#[no_mangle]
+pub extern "C" fn matrix_gram(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        Ok(this.gram())
+    })
+}
+
+// Gram matrix A * A^T
+#[no_mangle]
+pub extern "C" fn matrix_gram_rows(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        Ok(this.gram_rows())
+    })
+}
+
+// Symmetric rank-k update C = alpha * op(A) * op(A)^T + beta * C in place of C, where op(A) is
+// A^T when `trans` is equal to 1; C must not be the same matrix as A
+#[no_mangle]
+pub extern "C" fn matrix_syrk(
+    trans: i32,
+    alpha: c_double,
+    aptr: *const DoubleMatrix,
+    beta: c_double,
+    cptr: *mut DoubleMatrix
+) -> VoidResult
 {
     try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.mmul_assign(that);
+        let a = matrix_ref(aptr)?;
+        ensure_distinct(cptr, aptr)?;
+        let c = matrix_mut(cptr)?;
+        DoubleMatrix::try_syrk(trans == 1, alpha, a, beta, c)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_mv(ptr: *const DoubleMatrix, xptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let x = matrix_ref(xptr)?;
+        this.try_mv(x)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_tmv(ptr: *const DoubleMatrix, xptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let x = matrix_ref(xptr)?;
+        this.try_tmv(x)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_column_mins(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.column_mins());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_outer(xptr: *const DoubleMatrix, yptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let x = matrix_ref(xptr)?;
+        let y = matrix_ref(yptr)?;
+        DoubleMatrix::try_outer(x, y)
+    })
+}
+
+// Dot product of matrices with the same number of elements
+#[no_mangle]
+pub extern "C" fn matrix_dot(ptr: *const DoubleMatrix, aptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(aptr)?;
+        this.try_dot(that)
+    })
+}
+
+// Compute this = alpha * x + this in place
+#[no_mangle]
+pub extern "C" fn matrix_axpy(
+    ptr: *mut DoubleMatrix,
+    alpha: c_double,
+    xptr: *const DoubleMatrix
+) -> VoidResult
+{
+    try_catch_void(|| in_place(ptr, xptr, |this, x| this.try_axpy(alpha, x)))
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_column_mins(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.column_mins()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_column_maxs(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.column_maxs());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_column_maxs(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.column_maxs()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_column_means(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.column_means());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_column_means(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.column_means()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_column_sums(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.column_sums());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_column_sums(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.column_sums()))
 }
 
 
 #[no_mangle]
-pub extern "C" fn matrix_row_mins(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.row_mins());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_row_mins(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.row_mins()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_row_maxs(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.row_maxs());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_row_maxs(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.row_maxs()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_row_means(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.row_means());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_row_means(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.row_means()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_row_sums(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.row_sums());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_row_sums(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.row_sums()))
 }
 
 #[no_mangle]
 pub extern "C" fn matrix_put_column(
     ptr: *mut DoubleMatrix,
-    col: int32_t,
+    col: i32,
     aptr: *const DoubleMatrix
 ) -> VoidResult
 {
-    try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.put_column(col as usize, that);
-    })
+    try_catch_void(|| in_place(ptr, aptr, |this, that| this.try_put_column(col as usize, that)))
 }
 
 #[no_mangle]
 pub extern "C" fn matrix_get_column(
     ptr: *const DoubleMatrix,
-    col: int32_t
+    col: i32
 ) -> PtrResult
 {
     try_catch_ptr(|| {
-        let this = unsafe { &(*ptr) };
-        this.get_column(col as usize)
+        let this = matrix_ref(ptr)?;
+        this.try_get_column(col as usize)
     })
 }
 
 #[no_mangle]
 pub extern "C" fn matrix_put_row(
     ptr: *mut DoubleMatrix,
-    row: int32_t,
+    row: i32,
     aptr: *const DoubleMatrix
 ) -> VoidResult
 {
-    try_catch_void(|| {
-        let this = unsafe { &mut (*ptr) };
-        let that = unsafe { &(*aptr) };
-        this.put_row(row as usize, that);
-    })
+    try_catch_void(|| in_place(ptr, aptr, |this, that| this.try_put_row(row as usize, that)))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_get_row(ptr: *const DoubleMatrix, row: int32_t) -> PtrResult {
+pub extern "C" fn matrix_get_row(ptr: *const DoubleMatrix, row: i32) -> PtrResult {
     try_catch_ptr(|| {
-        let this = unsafe { &(*ptr) };
-        this.get_row(row as usize)
+        let this = matrix_ref(ptr)?;
+        this.try_get_row(row as usize)
     })
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_min(ptr: *const DoubleMatrix) -> c_double {
-    unsafe { (*ptr).min() }
+pub extern "C" fn matrix_min(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| Ok(matrix_ref(ptr)?.min()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_max(ptr: *const DoubleMatrix) -> c_double {
-    unsafe { (*ptr).max() }
+pub extern "C" fn matrix_max(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| Ok(matrix_ref(ptr)?.max()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_sum(ptr: *const DoubleMatrix) -> c_double {
-    unsafe { (*ptr).sum() }
+pub extern "C" fn matrix_sum(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| Ok(matrix_ref(ptr)?.sum()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_norm1(ptr: *const DoubleMatrix) -> c_double {
-    unsafe { (*ptr).norm1() }
+pub extern "C" fn matrix_norm1(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| Ok(matrix_ref(ptr)?.norm1()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_norm2(ptr: *const DoubleMatrix) -> c_double {
-    unsafe { (*ptr).norm2() }
+pub extern "C" fn matrix_norm2(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| Ok(matrix_ref(ptr)?.norm2()))
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_transpose(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.transpose());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_transpose(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.transpose()))
 }
 
 #[no_mangle]
 pub extern "C" fn matrix_diag(ptr: *const DoubleMatrix) -> PtrResult {
-    let this = unsafe { &(*ptr) };
     // return diagonal as column vector similar to jblas
-    try_catch_ptr(|| this.diag())
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_diag()
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_abs(ptr: *const DoubleMatrix) -> *const DoubleMatrix {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.abs());
-    Box::into_raw(matrix)
+pub extern "C" fn matrix_abs(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| Ok(matrix_ref(ptr)?.abs()))
 }
 
 // == Singular value decomposition methods ==
 
 #[no_mangle]
 pub extern "C" fn matrix_full_svd(ptr: *const DoubleMatrix) -> SvdResult {
-    let this = unsafe { &(*ptr) };
-    try_catch_svd(|| this.full_svd())
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_full_svd()
+    })
+}
+
+
+#[no_mangle]
+pub extern "C" fn matrix_singular_values(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| matrix_ref(ptr)?.try_singular_values())
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_svd_k(ptr: *const DoubleMatrix, k: i32) -> SvdResult {
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_svd(k as usize)
+    })
 }
 
+// Top k singular values with singular vectors selected by job, which is one of 'N' (values only),
+// 'U' (left), 'V' (right) or 'A' (both); vectors that are not computed are returned as NULL.
+#[no_mangle]
+pub extern "C" fn matrix_svd_job_k(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    job: c_char
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_svd_job(k as usize, SvdJob::try_from_char(job as u8 as char)?)
+    })
+}
+
+// Singular values with 0-based indices in [start, end) with singular vectors selected by job,
+// see `matrix_svd_job_k` for values; returns however many singular values were found.
+#[no_mangle]
+pub extern "C" fn matrix_svd_index_range(
+    ptr: *const DoubleMatrix,
+    start: i32,
+    end: i32,
+    job: c_char
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        let range = SvdRange::Index(cmp::max(start, 0) as usize, cmp::max(end, 0) as usize);
+        this.try_svd_range(range, SvdJob::try_from_char(job as u8 as char)?)
+    })
+}
+
+// Singular values in half-open interval (vl, vu] with singular vectors selected by job, `vu` can
+// be infinite; returns however many singular values were found, possibly none.
+#[no_mangle]
+pub extern "C" fn matrix_svd_value_range(
+    ptr: *const DoubleMatrix,
+    vl: c_double,
+    vu: c_double,
+    job: c_char
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        let job = SvdJob::try_from_char(job as u8 as char)?;
+        this.try_svd_range(SvdRange::Value(vl, vu), job)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_lansvd_k(ptr: *const DoubleMatrix, k: i32) -> SvdResult {
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_lansvd_with(k as usize, &LanczosOptions::new())
+    })
+}
 
+// Lanczos SVD with singular vectors selected by job, see `matrix_svd_job_k` for values.
 #[no_mangle]
-pub extern "C" fn matrix_singular_values(
-    ptr: *const DoubleMatrix
-) -> *const DoubleMatrix
+pub extern "C" fn matrix_lansvd_job_k(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    job: c_char
+) -> SvdResult
 {
-    let this = unsafe { &(*ptr) };
-    let matrix = Box::new(this.singular_values());
-    Box::into_raw(matrix)
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        let options = LanczosOptions::new().job(SvdJob::try_from_char(job as u8 as char)?);
+        this.try_lansvd_with(k as usize, &options)
+    })
 }
 
 #[no_mangle]
-pub extern "C" fn matrix_svd_k(ptr: *const DoubleMatrix, k: int32_t) -> SvdResult {
-    let this = unsafe { &(*ptr) };
-    try_catch_svd(|| this.svd(k as usize))
+pub extern "C" fn matrix_lansvd_which_k(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    which: c_char
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        let options = LanczosOptions::new().which(Which::try_from_char(which as u8 as char)?);
+        this.try_lansvd_with(k as usize, &options)
+    })
 }
 
+// Lanczos SVD with options, `which` is either 'L' (largest) or 'S' (smallest) singular values,
+// `dim` and `shifts` are set to default values when non-positive, `classical_gs` and
+// `extended_local_orth` are enabled when equal to 1.
 #[no_mangle]
-pub extern "C" fn matrix_lansvd_k(ptr: *const DoubleMatrix, k: int32_t) -> SvdResult {
-    let this = unsafe { &(*ptr) };
-    try_catch_svd(|| this.lansvd(k as usize))
+pub extern "C" fn matrix_lansvd_with(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    which: c_char,
+    tolerance: c_double,
+    max_restarts: i32,
+    dim: i32,
+    shifts: i32,
+    classical_gs: i32,
+    extended_local_orth: i32
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        let options = lanczos_options(which, tolerance, max_restarts, dim, shifts, classical_gs,
+            extended_local_orth)?;
+        this.try_lansvd_with(k as usize, &options)
+    })
+}
+
+// Lanczos SVD with options that returns error bounds and convergence diagnostics, see
+// `matrix_lansvd_with` for parameters and `LanczosResult` for result.
+#[no_mangle]
+pub extern "C" fn matrix_lansvd_result(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    which: c_char,
+    tolerance: c_double,
+    max_restarts: i32,
+    dim: i32,
+    shifts: i32,
+    classical_gs: i32,
+    extended_local_orth: i32
+) -> LanczosResult
+{
+    try_catch_lanczos(|| {
+        let this = matrix_ref(ptr)?;
+        let options = lanczos_options(which, tolerance, max_restarts, dim, shifts, classical_gs,
+            extended_local_orth)?;
+        this.try_lansvd_result(k as usize, &options)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_lansvd_pro_k(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    kmax: i32
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_lansvd_pro(k as usize, kmax as usize)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_rsvd_k(ptr: *const DoubleMatrix, k: i32) -> SvdResult {
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_rsvd_with(k as usize, 10, 2)
+    })
+}
+
+// Randomized SVD with number of oversampling columns and power iterations, negative values are
+// treated as 0.
+#[no_mangle]
+pub extern "C" fn matrix_rsvd_with(
+    ptr: *const DoubleMatrix,
+    k: i32,
+    oversampling: i32,
+    power_iters: i32
+) -> SvdResult
+{
+    try_catch_svd(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_rsvd_with(k as usize, cmp::max(oversampling, 0) as usize,
+            cmp::max(power_iters, 0) as usize)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_solve(
+    ptr: *const DoubleMatrix,
+    bptr: *const DoubleMatrix
+) -> PtrResult
+{
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(bptr)?;
+        this.try_solve(that)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_inverse(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_inverse()
+    })
+}
+
+// Determinant of square matrix
+#[no_mangle]
+pub extern "C" fn matrix_determinant(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_determinant()
+    })
+}
+
+// QR factorization, column pivoting is used when `pivoting` is equal to 1
+#[no_mangle]
+pub extern "C" fn matrix_qr(ptr: *const DoubleMatrix, pivoting: i32) -> QrResult {
+    try_catch_qr(|| {
+        let this = matrix_ref(ptr)?;
+        if pivoting == 1 { this.try_qr_pivoted() } else { this.try_qr() }
+    })
+}
+
+// Least squares solution using DGELSD, machine precision is used when `rcond` is negative
+#[no_mangle]
+pub extern "C" fn matrix_lstsq(
+    ptr: *const DoubleMatrix,
+    bptr: *const DoubleMatrix,
+    rcond: c_double
+) -> LstsqResult
+{
+    try_catch_lstsq(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(bptr)?;
+        this.try_lstsq(that, rcond)
+    })
+}
+
+// Least squares solution using DGELS for matrices of full rank
+#[no_mangle]
+pub extern "C" fn matrix_lstsq_qr(
+    ptr: *const DoubleMatrix,
+    bptr: *const DoubleMatrix
+) -> LstsqResult
+{
+    try_catch_lstsq(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(bptr)?;
+        this.try_lstsq_qr(that)
+    })
+}
+
+// Lower triangular Cholesky factor, ERR_SINGULAR is returned when matrix is not positive definite
+// with order of the leading minor that is not positive as `err_info`
+#[no_mangle]
+pub extern "C" fn matrix_cholesky(ptr: *const DoubleMatrix) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_cholesky()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_cholesky_solve(
+    ptr: *const DoubleMatrix,
+    bptr: *const DoubleMatrix
+) -> PtrResult
+{
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        let that = matrix_ref(bptr)?;
+        this.try_cholesky_solve(that)
+    })
+}
+
+// Returns 1 if matrix is symmetric positive definite, 0 otherwise
+#[no_mangle]
+pub extern "C" fn matrix_is_positive_definite(ptr: *const DoubleMatrix) -> IntResult {
+    try_catch_int(|| Ok(if matrix_ref(ptr)?.is_positive_definite() { 1 } else { 0 }))
+}
+
+// Eigendecomposition of symmetric matrix, eigenvalues are in ascending order
+#[no_mangle]
+pub extern "C" fn matrix_eigh(ptr: *const DoubleMatrix) -> EigenResult {
+    try_catch_eigen(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_eigh()
+    })
+}
+
+// k largest eigenvalues of symmetric matrix in descending order
+#[no_mangle]
+pub extern "C" fn matrix_eigh_top_k(ptr: *const DoubleMatrix, k: i32) -> EigenResult {
+    try_catch_eigen(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_eigh_top(k as usize)
+    })
+}
+
+// Eigenvalues with 0-based indices in [start, end) in ascending order
+#[no_mangle]
+pub extern "C" fn matrix_eigh_index_range(
+    ptr: *const DoubleMatrix,
+    start: i32,
+    end: i32
+) -> EigenResult
+{
+    try_catch_eigen(|| {
+        let this = matrix_ref(ptr)?;
+        let range = EigenRange::Index(cmp::max(start, 0) as usize, cmp::max(end, 0) as usize);
+        this.try_eigh_range(range)
+    })
+}
+
+// Eigenvalues in half-open interval (vl, vu], bounds can be infinite
+#[no_mangle]
+pub extern "C" fn matrix_eigh_value_range(
+    ptr: *const DoubleMatrix,
+    vl: c_double,
+    vu: c_double
+) -> EigenResult
+{
+    try_catch_eigen(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_eigh_range(EigenRange::Value(vl, vu))
+    })
+}
+
+// Eigendecomposition of general square matrix, left and right eigenvectors are computed when
+// corresponding flag is equal to 1
+#[no_mangle]
+pub extern "C" fn matrix_eig(
+    ptr: *const DoubleMatrix,
+    left: i32,
+    right: i32
+) -> GeneralEigenResult
+{
+    try_catch_general_eigen(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_eig_with(left == 1, right == 1)
+    })
+}
+
+// Pseudo-inverse, default tolerance is used when `rcond` is negative
+#[no_mangle]
+pub extern "C" fn matrix_pinv(ptr: *const DoubleMatrix, rcond: c_double) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_pinv(rcond)
+    })
+}
+
+// Numerical rank, default tolerance is used when `tol` is negative
+#[no_mangle]
+pub extern "C" fn matrix_rank(ptr: *const DoubleMatrix, tol: c_double) -> IntResult {
+    try_catch_int(|| {
+        let this = matrix_ref(ptr)?;
+        Ok(this.try_rank(tol)? as i32)
+    })
+}
+
+// Condition number, infinity is returned for rank-deficient matrix
+#[no_mangle]
+pub extern "C" fn matrix_cond(ptr: *const DoubleMatrix) -> ScalarResult {
+    try_catch_scalar(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_cond()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_null_space(ptr: *const DoubleMatrix, tol: c_double) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_null_space(tol)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn matrix_range_space(ptr: *const DoubleMatrix, tol: c_double) -> PtrResult {
+    try_catch_ptr(|| {
+        let this = matrix_ref(ptr)?;
+        this.try_range_space(tol)
+    })
 }
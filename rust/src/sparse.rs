@@ -0,0 +1,431 @@
+// Copyright (c) 2017 sadikovi
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Module that defines sparse matrix used as input for Lanczos SVD.
+
+use libc::c_char;
+use internal::{DoubleMatrix, LanczosSvd, MatrixError, MatrixResult, SVD};
+use internal::{try_lansvd_op, unwrap_or_panic};
+use lanczos::{LanczosOptions, LinearOperator, sparse_matmul};
+
+// Sparse matrix in compressed sparse column (CSC) format.
+// Index array contains column pointers (cols + 1 elements) followed by row indices of non-zero
+// values, so it can be passed to `sparse_matmul` as `iparm` without copying, values are passed
+// as `dparm`.
+#[derive(Clone, Debug)]
+pub struct SparseMatrix {
+    rows: usize, // number of rows in this matrix
+    cols: usize, // number of columns in this matrix
+    index: Vec<i32>, // column pointers followed by row indices
+    values: Vec<f64> // non-zero values in column major order
+}
+
+impl SparseMatrix {
+    // Create new sparse matrix from CSC arrays: column pointers `indptr` (cols + 1 elements),
+    // row indices `indices` and non-zero values `values` (both nnz elements).
+    pub fn new(
+        rows: usize,
+        cols: usize,
+        indptr: &[i32],
+        indices: &[i32],
+        values: Vec<f64>
+    ) -> Self {
+        unwrap_or_panic(Self::try_new(rows, cols, indptr, indices, values))
+    }
+
+    // Create new sparse matrix from CSC arrays, returns error for invalid dimensions, column
+    // pointers or row indices
+    pub fn try_new(
+        rows: usize,
+        cols: usize,
+        indptr: &[i32],
+        indices: &[i32],
+        values: Vec<f64>
+    ) -> MatrixResult<Self> {
+        ensure!(rows <= i32::MAX as usize && cols < i32::MAX as usize,
+            MatrixError::InvalidArgument(
+                format!("Dimensions are too large: {} x {}.", rows, cols)));
+        ensure!(indptr.len() == cols + 1, MatrixError::ShapeMismatch(
+            format!("Invalid number of column pointers: {} != {}.", indptr.len(), cols + 1)));
+        ensure!(indices.len() == values.len(), MatrixError::ShapeMismatch(format!(
+            "Dimensions mismatch: {} indices != {} values.", indices.len(), values.len())));
+        ensure!(indptr[0] == 0 && indptr[cols] as usize == values.len(),
+            MatrixError::InvalidArgument(format!(
+                "Invalid column pointers, expected range [0, {}], found [{}, {}].",
+                values.len(), indptr[0], indptr[cols])));
+        for j in 0..cols {
+            ensure!(indptr[j] <= indptr[j + 1], MatrixError::InvalidArgument(format!(
+                "Column pointers are not sorted: {} > {}.", indptr[j], indptr[j + 1])));
+        }
+        for &i in indices {
+            ensure!(i >= 0 && (i as usize) < rows, MatrixError::IndexOutOfBounds(
+                format!("Invalid row index {} ({} rows).", i, rows)));
+        }
+
+        let mut index = Vec::with_capacity(indptr.len() + indices.len());
+        index.extend_from_slice(indptr);
+        index.extend_from_slice(indices);
+        Ok(SparseMatrix { rows, cols, index, values })
+    }
+
+    // Create new sparse matrix from CSR arrays: row pointers `indptr` (rows + 1 elements), column
+    // indices `indices` and non-zero values `values`; data is converted into CSC format.
+    pub fn from_csr(
+        rows: usize,
+        cols: usize,
+        indptr: &[i32],
+        indices: &[i32],
+        values: &[f64]
+    ) -> Self {
+        unwrap_or_panic(Self::try_from_csr(rows, cols, indptr, indices, values))
+    }
+
+    // Create new sparse matrix from CSR arrays, returns error for invalid row pointers or column
+    // indices
+    pub fn try_from_csr(
+        rows: usize,
+        cols: usize,
+        indptr: &[i32],
+        indices: &[i32],
+        values: &[f64]
+    ) -> MatrixResult<Self> {
+        ensure!(indptr.len() == rows + 1, MatrixError::ShapeMismatch(
+            format!("Invalid number of row pointers: {} != {}.", indptr.len(), rows + 1)));
+        ensure!(indices.len() == values.len(), MatrixError::ShapeMismatch(format!(
+            "Dimensions mismatch: {} indices != {} values.", indices.len(), values.len())));
+        ensure!(indptr[0] == 0 && indptr[rows] as usize == values.len(),
+            MatrixError::InvalidArgument(format!(
+                "Invalid row pointers, expected range [0, {}], found [{}, {}].",
+                values.len(), indptr[0], indptr[rows])));
+        for i in 0..rows {
+            ensure!(indptr[i] <= indptr[i + 1], MatrixError::InvalidArgument(format!(
+                "Row pointers are not sorted: {} > {}.", indptr[i], indptr[i + 1])));
+        }
+        for &j in indices {
+            ensure!(j >= 0 && (j as usize) < cols, MatrixError::IndexOutOfBounds(
+                format!("Invalid column index {} ({} columns).", j, cols)));
+        }
+
+        // count non-zero values in each column and compute column pointers
+        let mut colptr = vec![0i32; cols + 1];
+        for &j in indices {
+            colptr[j as usize + 1] += 1;
+        }
+        for j in 0..cols {
+            colptr[j + 1] += colptr[j];
+        }
+
+        let mut next = colptr.clone();
+        let mut rowind = vec![0i32; indices.len()];
+        let mut data = vec![0f64; values.len()];
+        for i in 0..rows {
+            for p in indptr[i] as usize..indptr[i + 1] as usize {
+                let j = indices[p] as usize;
+                let dest = next[j] as usize;
+                rowind[dest] = i as i32;
+                data[dest] = values[p];
+                next[j] += 1;
+            }
+        }
+
+        Self::try_new(rows, cols, &colptr, &rowind, data)
+    }
+
+    // Create new sparse matrix from dense matrix, only non-zero elements are stored
+    pub fn from_dense(matrix: &DoubleMatrix) -> Self {
+        let (rows, cols) = matrix.shape();
+        let mut indptr = vec![0i32; cols + 1];
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        for j in 0..cols {
+            for i in 0..rows {
+                let value = matrix.get(i, j);
+                if value != 0f64 {
+                    indices.push(i as i32);
+                    values.push(value);
+                }
+            }
+            indptr[j + 1] = values.len() as i32;
+        }
+        Self::new(rows, cols, &indptr, &indices, values)
+    }
+
+    // Convert sparse matrix into dense matrix
+    pub fn to_dense(&self) -> DoubleMatrix {
+        let mut matrix = DoubleMatrix::zeros(self.rows, self.cols);
+        let indptr = self.indptr();
+        let indices = self.indices();
+        for j in 0..self.cols {
+            let (start, end) = (indptr[j] as usize, indptr[j + 1] as usize);
+            for (&i, &value) in indices[start..end].iter().zip(&self.values[start..end]) {
+                matrix.put(i as usize, j, value);
+            }
+        }
+        matrix
+    }
+
+    // Return rows and columns as shape tuple
+    #[inline]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    // Return number of rows
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // Return number of columns
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // Return number of stored non-zero values
+    #[inline]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    // Return column pointers
+    #[inline]
+    pub fn indptr(&self) -> &[i32] {
+        &self.index[..self.cols + 1]
+    }
+
+    // Return row indices of non-zero values
+    #[inline]
+    pub fn indices(&self) -> &[i32] {
+        &self.index[self.cols + 1..]
+    }
+
+    // Return non-zero values
+    #[inline]
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    // Experimental svd for top k singular values, see `DoubleMatrix::lansvd` for details.
+    // Matrix is never materialized as dense, matrix-vector products are computed by
+    // `sparse_matmul`, that receives values as `dparm` and indices as `iparm`.
+    pub fn lansvd(&self, k: usize) -> SVD {
+        self.lansvd_with(k, &LanczosOptions::new())
+    }
+
+    // Experimental svd for top k singular values, returns error for invalid k or when not all
+    // singular triplets converged
+    pub fn try_lansvd(&self, k: usize) -> MatrixResult<SVD> {
+        self.try_lansvd_with(k, &LanczosOptions::new())
+    }
+
+    // Experimental svd for top k singular values with provided options.
+    pub fn lansvd_with(&self, k: usize, options: &LanczosOptions) -> SVD {
+        unwrap_or_panic(self.try_lansvd_with(k, options))
+    }
+
+    // Experimental svd for top k singular values with provided options, returns error instead of
+    // panicking
+    pub fn try_lansvd_with(&self, k: usize, options: &LanczosOptions) -> MatrixResult<SVD> {
+        self.try_lansvd_result(k, options)?.try_into_svd()
+    }
+
+    // Experimental svd for top k singular values with convergence diagnostics.
+    pub fn lansvd_result(&self, k: usize, options: &LanczosOptions) -> LanczosSvd {
+        unwrap_or_panic(self.try_lansvd_result(k, options))
+    }
+
+    // Experimental svd for top k singular values with convergence diagnostics, returns error for
+    // invalid options
+    pub fn try_lansvd_result(
+        &self,
+        k: usize,
+        options: &LanczosOptions
+    ) -> MatrixResult<LanczosSvd> {
+        let (rows, cols) = self.shape();
+        let (dparm, iparm) = (self.values.as_ptr(), self.index.as_ptr());
+        unsafe { try_lansvd_op(rows, cols, k, options, Some(sparse_matmul), dparm, iparm) }
+    }
+
+    // Compute y = op(A) * x with `sparse_matmul`, where op is selected by `transa`
+    fn aprod(&self, transa: u8, x: &[f64], y: &mut [f64]) {
+        let (xlen, ylen) = if transa == b'N' { (self.cols, self.rows) } else { (self.rows, self.cols) };
+        assert_eq!(x.len(), xlen, "Invalid length of input vector: {} != {}.", x.len(), xlen);
+        assert_eq!(y.len(), ylen, "Invalid length of output vector: {} != {}.", y.len(), ylen);
+        let (m, n) = (self.rows as i32, self.cols as i32);
+        // iparm is declared as mutable, but it is never modified by `sparse_matmul`
+        let iparm = self.index.as_ptr() as *mut i32;
+        sparse_matmul(&(transa as c_char), &m, &n, x.as_ptr(), y.as_mut_ptr(),
+            self.values.as_ptr(), iparm);
+    }
+}
+
+impl LinearOperator for SparseMatrix {
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn matvec(&self, x: &[f64], y: &mut [f64]) {
+        self.aprod(b'N', x, y);
+    }
+
+    fn rmatvec(&self, x: &[f64], y: &mut [f64]) {
+        self.aprod(b'T', x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_eps(a: &DoubleMatrix, b: &DoubleMatrix, epsilon: f64) {
+        assert_eq!(a.shape(), b.shape(), "Shape mismatch: {:?} != {:?}", a, b);
+        let vec1 = a.data();
+        let vec2 = b.data();
+        for i in 0..vec1.len() {
+            assert!((vec1[i] - vec2[i]).abs() <= epsilon,
+                "Element mismatch {} != {}; a: {:?}, b: {:?}", vec1[i], vec2[i], a, b);
+        }
+    }
+
+    fn test_matrix() -> DoubleMatrix {
+        DoubleMatrix::from_row_slice(4, 5, &[
+            1.0, 0.0, 0.0, 2.0, 0.0,
+            0.0, 0.0, 3.0, 0.0, 0.0,
+            0.0, 4.0, 0.0, 0.0, 5.0,
+            6.0, 0.0, 0.0, 0.0, 7.0
+        ])
+    }
+
+    #[test]
+    fn test_new() {
+        let matrix = SparseMatrix::new(3, 2, &[0, 1, 3], &[2, 0, 1], vec![1.0, 2.0, 3.0]);
+        assert_eq!(matrix.shape(), (3, 2));
+        assert_eq!(matrix.nnz(), 3);
+        assert_eq!(matrix.indptr(), &[0, 1, 3]);
+        assert_eq!(matrix.indices(), &[2, 0, 1]);
+        assert_eq!(matrix.values(), &[1.0, 2.0, 3.0]);
+        assert_matrix_eps(&matrix.to_dense(),
+            &DoubleMatrix::new(3, 2, vec![0.0, 0.0, 1.0, 2.0, 3.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid number of column pointers: 2 != 3.")]
+    fn test_new_invalid_indptr() {
+        SparseMatrix::new(3, 2, &[0, 1], &[0], vec![1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid row index 3 (3 rows).")]
+    fn test_new_invalid_row_index() {
+        SparseMatrix::new(3, 2, &[0, 1, 1], &[3], vec![1.0]);
+    }
+
+    #[test]
+    fn test_try_new_invalid() {
+        match SparseMatrix::try_new(3, 2, &[0, 1, 1], &[3], vec![1.0]) {
+            Err(MatrixError::IndexOutOfBounds(msg)) => {
+                assert_eq!(msg, "Invalid row index 3 (3 rows).")
+            },
+            res => panic!("Unexpected result {:?}", res)
+        }
+        match SparseMatrix::try_from_csr(2, 3, &[0, 1], &[0], &[1.0]) {
+            Err(MatrixError::ShapeMismatch(msg)) => {
+                assert_eq!(msg, "Invalid number of row pointers: 2 != 3.")
+            },
+            res => panic!("Unexpected result {:?}", res)
+        }
+    }
+
+    #[test]
+    fn test_from_to_dense() {
+        let dense = test_matrix();
+        let matrix = SparseMatrix::from_dense(&dense);
+        assert_eq!(matrix.nnz(), 7);
+        assert_matrix_eps(&matrix.to_dense(), &dense, 0.0);
+    }
+
+    #[test]
+    fn test_from_csr() {
+        let matrix = SparseMatrix::from_csr(4, 5, &[0, 2, 3, 5, 7], &[0, 3, 2, 1, 4, 0, 4],
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+        let exp = SparseMatrix::from_dense(&test_matrix());
+        assert_eq!(matrix.indptr(), exp.indptr());
+        assert_eq!(matrix.indices(), exp.indices());
+        assert_eq!(matrix.values(), exp.values());
+    }
+
+    #[test]
+    #[should_panic(expected = "Row pointers are not sorted: 2 > 1.")]
+    fn test_from_csr_unsorted_indptr() {
+        SparseMatrix::from_csr(3, 2, &[0, 2, 1, 3], &[0, 1, 0], &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_try_from_csr_invalid_indptr() {
+        match SparseMatrix::try_from_csr(2, 2, &[0, 1, 3], &[0, 1], &[1.0, 2.0]) {
+            Err(MatrixError::InvalidArgument(msg)) => {
+                assert_eq!(msg, "Invalid row pointers, expected range [0, 2], found [0, 3].")
+            },
+            res => panic!("Unexpected result {:?}", res)
+        }
+        assert!(SparseMatrix::try_from_csr(2, 2, &[1, 1, 2], &[0, 1], &[1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_matvec() {
+        let dense = test_matrix();
+        let matrix = SparseMatrix::from_dense(&dense);
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let mut y = [0f64; 4];
+        let mut exp = [0f64; 4];
+        matrix.matvec(&x, &mut y);
+        dense.matvec(&x, &mut exp);
+        assert_eq!(y, exp);
+
+        let x = [1.0, 2.0, 3.0, 4.0];
+        let mut y = [0f64; 5];
+        let mut exp = [0f64; 5];
+        matrix.rmatvec(&x, &mut y);
+        dense.rmatvec(&x, &mut exp);
+        assert_eq!(y, exp);
+    }
+
+    #[test]
+    fn test_lansvd() {
+        let dense = test_matrix();
+        let svd = SparseMatrix::from_dense(&dense).lansvd(2);
+        let exp = dense.singular_values();
+        assert_matrix_eps(&svd.s, &DoubleMatrix::new(2, 1, exp.data()[..2].to_vec()), 1e-6);
+        assert_eq!(svd.u.unwrap().shape(), (4, 2));
+        assert_eq!(svd.v.unwrap().shape(), (5, 2));
+    }
+
+    #[test]
+    fn test_try_lansvd_invalid_k() {
+        let matrix = SparseMatrix::from_dense(&test_matrix());
+        match matrix.try_lansvd(5) {
+            Err(MatrixError::InvalidArgument(msg)) => {
+                assert_eq!(msg, "Invalid number of singular values: 5.")
+            },
+            res => panic!("Unexpected result {:?}", res.map(|svd| svd.s))
+        }
+    }
+}